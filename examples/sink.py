@@ -18,7 +18,7 @@ class MySink(Sink):
 
     async def iteration(self) -> None:
         message = await self.input.recv_async()
-        logging.info("timestamp: {}, payload: {}".format(message.timestamp(), message.payload()))
+        logging.info("timestamp: {}, payload: {}".format(message.timestamp(), bytes(message.payload())))
 
 
     def finalize(self) -> None:
@@ -0,0 +1,126 @@
+//
+// Copyright © 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! A single library exporting the Source, Operator and Sink wrappers of Zenoh-Flow nodes implemented in Python.
+//!
+//! All the logic is implemented by the `zenoh-flow-python` crate, see [PythonState].
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use zenoh_flow_nodes::{
+    prelude as zf,
+    prelude::{export_operator, export_sink, export_source, Operator, Sink, Source},
+};
+use zenoh_flow_python::{NodePorts, PythonState};
+
+/// Forwards the logs of the Python node, and of the Rust code, to the logger of the process.
+fn init_logging(kind: &str, context: &zf::Context) {
+    pyo3_pylogger::register(&format!("zenoh_flow_python_{kind}::{}", context.node_id()));
+    let _ = tracing_subscriber::fmt::try_init();
+}
+
+#[export_source]
+struct ZenohFlowPythonSource {
+    state: Arc<PythonState>,
+}
+
+#[async_trait]
+impl Source for ZenohFlowPythonSource {
+    async fn new(
+        context: zf::Context,
+        configuration: zf::Configuration,
+        outputs: zf::Outputs,
+    ) -> zf::Result<Self> {
+        init_logging("source", &context);
+
+        Ok(Self {
+            state: Arc::new(PythonState::load(
+                context,
+                configuration,
+                NodePorts::Source { outputs },
+            )?),
+        })
+    }
+}
+
+#[async_trait]
+impl zf::Node for ZenohFlowPythonSource {
+    async fn iteration(&self) -> zf::Result<()> {
+        self.state.iteration()
+    }
+}
+
+#[export_operator]
+struct ZenohFlowPythonOperator {
+    state: Arc<PythonState>,
+}
+
+#[async_trait]
+impl Operator for ZenohFlowPythonOperator {
+    async fn new(
+        context: zf::Context,
+        configuration: zf::Configuration,
+        inputs: zf::Inputs,
+        outputs: zf::Outputs,
+    ) -> zf::Result<Self> {
+        init_logging("operator", &context);
+
+        Ok(Self {
+            state: Arc::new(PythonState::load(
+                context,
+                configuration,
+                NodePorts::Operator { inputs, outputs },
+            )?),
+        })
+    }
+}
+
+#[async_trait]
+impl zf::Node for ZenohFlowPythonOperator {
+    async fn iteration(&self) -> zf::Result<()> {
+        self.state.iteration()
+    }
+}
+
+#[export_sink]
+struct ZenohFlowPythonSink {
+    state: Arc<PythonState>,
+}
+
+#[async_trait]
+impl Sink for ZenohFlowPythonSink {
+    async fn new(
+        context: zf::Context,
+        configuration: zf::Configuration,
+        inputs: zf::Inputs,
+    ) -> zf::Result<Self> {
+        init_logging("sink", &context);
+
+        Ok(Self {
+            state: Arc::new(PythonState::load(
+                context,
+                configuration,
+                NodePorts::Sink { inputs },
+            )?),
+        })
+    }
+}
+
+#[async_trait]
+impl zf::Node for ZenohFlowPythonSink {
+    async fn iteration(&self) -> zf::Result<()> {
+        self.state.iteration()
+    }
+}
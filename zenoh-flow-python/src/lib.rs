@@ -12,44 +12,71 @@
 //   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 //
 
-use std::{fmt::Display, path::PathBuf, sync::Arc};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    ffi::c_int,
+    fmt::Display,
+    hash::{Hash, Hasher},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use anyhow::anyhow;
 use pyo3::{
-    exceptions::{PyTypeError, PyValueError},
+    basic::CompareOp,
+    buffer::PyBuffer,
+    create_exception,
+    exceptions::{PyStopAsyncIteration, PyValueError},
+    ffi,
     prelude::*,
-    types::{PyDict, PyList},
+    types::{PyBool, PyDict, PyFloat, PyList, PyLong, PyString, PyTuple},
     PyErr, PyObject, PyResult, Python, ToPyObject,
 };
-use pyo3_asyncio::TaskLocals;
 use serde_json::Value;
 use zenoh_flow_nodes::prelude as zf;
 
-#[derive(Clone)]
-pub struct PythonState {
-    pub node_instance: Arc<PyObject>,
-    pub task_locals: Arc<TaskLocals>,
-}
+mod ndarray;
+pub use ndarray::{NDArrayCodec, NDArrayHeader};
 
-impl Drop for PythonState {
-    fn drop(&mut self) {
-        Python::with_gil(|py| {
-            self.node_instance
-                .call_method0(py, "finalize")
-                .expect("Failed to call `finalize` on the internal Python state");
-        });
-    }
-}
+mod record_batch;
+pub use record_batch::RecordBatchCodec;
+#[cfg(feature = "arrow")]
+pub use record_batch::{record_batch_from_ipc, record_batch_to_ipc};
+
+mod wrapper;
+pub use wrapper::{
+    ConfigurationPoller, ConfigurationWatcher, NodePorts, PythonState, WrapperConfiguration,
+    WRAPPER_CONFIGURATION_KEY,
+};
 
 #[pymodule]
-fn zenoh_flow_python(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+fn zenoh_flow_python(py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    m.add("ZenohFlowError", py.get_type::<ZenohFlowError>())?;
+    m.add("DisconnectedError", py.get_type::<DisconnectedError>())?;
+    m.add("ChannelFullError", py.get_type::<ChannelFullError>())?;
+    m.add("PortNotFoundError", py.get_type::<PortNotFoundError>())?;
+    m.add("SerializationError", py.get_type::<SerializationError>())?;
     m.add_class::<InstanceId>()?;
     m.add_class::<RuntimeId>()?;
     m.add_class::<Context>()?;
+    m.add_class::<Configuration>()?;
     m.add_class::<Timestamp>()?;
+    m.add_class::<PayloadBuffer>()?;
     m.add_class::<LinkMessage>()?;
     m.add_class::<InputRaw>()?;
+    m.add_class::<Selector>()?;
+    m.add_class::<Input>()?;
     m.add_class::<Inputs>()?;
+    m.add_class::<OutputRaw>()?;
+    m.add_class::<Output>()?;
+    m.add_class::<Outputs>()?;
+    m.add_class::<NDArrayCodec>()?;
+    m.add_class::<RecordBatchCodec>()?;
     Ok(())
 }
 
@@ -115,9 +142,17 @@ impl Context {
     pub fn node_id(&self) -> &str {
         self.0.node_id()
     }
+
+    // TODO Expose `new_timestamp` and `update_hlc`, backed by the Hybrid Logical Clock of the Zenoh-Flow runtime, such
+    //      that Python nodes can generate timestamps consistent with it.
+    //
+    //      This is currently not possible: `zf::Context` does not give access to the HLC, which is only held
+    //      (privately) by the `zf::OutputRaw`. Using an HLC local to the node instead would produce timestamps with a
+    //      different identifier and would not preserve causality with the runtime, defeating the purpose.
 }
 
 #[pyo3::pyclass]
+#[derive(Clone)]
 pub struct Timestamp(pub(crate) zf::Timestamp);
 
 impl From<&zf::Timestamp> for Timestamp {
@@ -128,53 +163,282 @@ impl From<&zf::Timestamp> for Timestamp {
 
 #[pyo3::pymethods]
 impl Timestamp {
+    /// Creates a Timestamp from its raw NTP64 time and the hexadecimal representation of its HLC identifier.
+    #[new]
+    fn new(ntp64: u64, id: &str) -> PyResult<Self> {
+        Ok(Self(zf::Timestamp::new(
+            zf::NTP64(ntp64),
+            id.parse()
+                .map_err(|e| ZFError::from(anyhow!("Invalid HLC identifier < {id} >: {e:?}")))?,
+        )))
+    }
+
+    /// Creates a Timestamp from a `datetime.datetime` and the hexadecimal representation of an HLC identifier.
+    ///
+    /// A naive `datetime` is interpreted as local time, exactly as Python does.
+    #[staticmethod]
+    fn from_datetime(datetime: &PyAny, id: &str) -> PyResult<Self> {
+        let py = datetime.py();
+        let py_datetime = py.import("datetime")?;
+        let utc = py_datetime.getattr("timezone")?.getattr("utc")?;
+        let epoch = py_datetime
+            .getattr("datetime")?
+            .call_method1("fromtimestamp", (0, utc))?;
+        let elapsed = datetime
+            .call_method1("astimezone", (utc,))?
+            .call_method1("__sub__", (epoch,))?;
+
+        let days: i64 = elapsed.getattr("days")?.extract()?;
+        let seconds: i64 = elapsed.getattr("seconds")?.extract()?;
+        let microseconds: u32 = elapsed.getattr("microseconds")?.extract()?;
+        let seconds = u64::try_from(days * 86_400 + seconds)
+            .map_err(|_| ZFError::from(anyhow!("Cannot create a Timestamp before UNIX_EPOCH")))?;
+        // NOTE: The seconds of an NTP64 time are stored on 32 bits, `NTP64::from` panics beyond.
+        if seconds > u64::from(u32::MAX) {
+            return Err(ZFError::from(anyhow!(
+                "Cannot create a Timestamp after the end of the NTP64 era (2106-02-07T06:28:15Z)"
+            ))
+            .into());
+        }
+
+        Self::new(
+            zf::NTP64::from(Duration::new(seconds, microseconds * 1_000)).as_u64(),
+            id,
+        )
+    }
+
     fn time(&self) -> u64 {
         // NOTE: u64::MAX, if converted from **nanoseconds** to a timestamp, will cease to function in 2554. Given we're
         // converting to **milliseconds** we should be safe.
         self.0.get_time().to_duration().as_millis() as u64
     }
 
+    fn time_ns(&self) -> u64 {
+        // NOTE: See the note in `time` above, we should be safe until 2554.
+        self.0.get_time().to_duration().as_nanos() as u64
+    }
+
+    fn ntp64(&self) -> u64 {
+        self.0.get_time().as_u64()
+    }
+
     fn id(&self) -> String {
         self.0.get_id().to_string()
     }
+
+    /// Returns a timezone-aware (UTC) `datetime.datetime`.
+    ///
+    /// As `datetime` has a resolution of a microsecond, the nanoseconds are truncated.
+    fn to_datetime<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let duration = self.0.get_time().to_duration();
+        let py_datetime = py.import("datetime")?;
+        let epoch = py_datetime.getattr("datetime")?.call_method1(
+            "fromtimestamp",
+            (0, py_datetime.getattr("timezone")?.getattr("utc")?),
+        )?;
+        let elapsed = py_datetime.getattr("timedelta")?.call1((
+            0,
+            duration.as_secs(),
+            duration.subsec_micros(),
+        ))?;
+
+        epoch.call_method1("__add__", (elapsed,))
+    }
+
+    fn __richcmp__(&self, other: &Self, op: CompareOp) -> bool {
+        op.matches(self.0.cmp(&other.0))
+    }
+
+    fn __hash__(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.0.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// The timestamp that can be associated with a payload when sending it on an [OutputRaw]: either a full [Timestamp]
+/// or a number of milliseconds elapsed since UNIX_EPOCH.
+#[derive(FromPyObject)]
+pub enum TimestampArg {
+    Timestamp(Timestamp),
+    Milliseconds(u64),
 }
 
+/// A read-only, zero-copy, view over the bytes of a payload.
+///
+/// This structure implements the Python buffer protocol: the bytes it exposes are not copied, they are borrowed from
+/// the underlying storage which is kept alive, through the [Arc], for as long as a buffer over it exists.
+#[pyo3::pyclass]
+pub struct PayloadBuffer(pub(crate) Arc<Vec<u8>>);
+
+impl PayloadBuffer {
+    /// Returns a read-only `memoryview` over the provided bytes.
+    pub(crate) fn view(py: Python<'_>, bytes: Arc<Vec<u8>>) -> PyResult<&PyAny> {
+        let buffer = PyCell::new(py, PayloadBuffer(bytes))?;
+        // SAFETY: `PyMemoryView_FromObject` returns a new reference, or NULL with an exception set.
+        unsafe { py.from_owned_ptr_or_err(ffi::PyMemoryView_FromObject(buffer.as_ptr())) }
+    }
+}
+
+#[pyo3::pymethods]
+impl PayloadBuffer {
+    /// # Safety
+    ///
+    /// `PyBuffer_FillInfo` increments the reference count of `slf`, which owns the bytes pointed at by `view`, and
+    /// refuses to fill a writable buffer as we declare it read-only.
+    unsafe fn __getbuffer__(
+        slf: PyRef<'_, Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        let bytes = slf.0.as_slice();
+        if ffi::PyBuffer_FillInfo(
+            view,
+            slf.as_ptr(),
+            bytes.as_ptr() as *mut _,
+            bytes.len() as ffi::Py_ssize_t,
+            1,
+            flags,
+        ) == -1
+        {
+            return Err(PyErr::fetch(slf.py()));
+        }
+
+        Ok(())
+    }
+
+    unsafe fn __releasebuffer__(&self, _view: *mut ffi::Py_buffer) {}
+
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+}
+
+/// A payload borrowed from a Python object implementing the buffer protocol.
+///
+/// The bytes of the object are exposed through a read-only `memoryview` cast to unsigned bytes, which lets any
+/// C-contiguous buffer (`bytes`, `bytearray`, `memoryview`, numpy arrays, ...) be sent, if zero-copy is enabled on
+/// the Output, without first copying it into a `Vec<u8>`. The exported [PyBuffer] then pins the memory of the object
+/// until the last receiver drops the payload.
+pub(crate) struct PythonBuffer {
+    view: PyObject,
+    buffer: PyBuffer<u8>,
+}
+
+impl PythonBuffer {
+    pub(crate) fn new(object: &PyAny) -> PyResult<Self> {
+        let py = object.py();
+        // SAFETY: `PyMemoryView_FromObject` returns a new reference, or NULL with an exception set.
+        let view: &PyAny =
+            unsafe { py.from_owned_ptr_or_err(ffi::PyMemoryView_FromObject(object.as_ptr()))? };
+        let view = view
+            .call_method1("cast", ("B",))?
+            .call_method0("toreadonly")?;
+
+        Ok(Self {
+            buffer: PyBuffer::get(view)?,
+            view: view.into(),
+        })
+    }
+
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        // SAFETY: the view was cast to a C-contiguous, one-dimensional, buffer of unsigned bytes and its memory stays
+        // valid for as long as `self.buffer` is not released.
+        unsafe {
+            std::slice::from_raw_parts(self.buffer.buf_ptr() as *const u8, self.buffer.len_bytes())
+        }
+    }
+
+    /// Converts this buffer into a [zf::Payload].
+    ///
+    /// By default the bytes are copied into a `Payload::Bytes`, which any node can receive. With `zero_copy`, the
+    /// buffer is wrapped in a `Payload::Typed`: the bytes are then only copied if the payload has to be serialised,
+    /// i.e. if it has to be sent to a node running in another process. As a Rust node running in the same process
+    /// cannot downcast it, zero-copy must only be enabled for Outputs connected to Python nodes.
+    pub(crate) fn into_payload(self, zero_copy: bool) -> zf::Payload {
+        if !zero_copy {
+            return self.as_slice().to_vec().into();
+        }
+
+        zf::Payload::Typed((
+            Arc::new(self),
+            Arc::new(|buffer: &mut Vec<u8>, data: Arc<dyn zf::SendSyncAny>| {
+                let python_buffer = data
+                    .as_any()
+                    .downcast_ref::<PythonBuffer>()
+                    .ok_or_else(|| anyhow!("Expected a `PythonBuffer`"))?;
+                buffer.extend_from_slice(python_buffer.as_slice());
+                Ok(())
+            }),
+        ))
+    }
+}
+
+// TODO Support per-message attachments (i.e. a key/value map sent alongside the payload and exposed as
+//      `LinkMessage.attachments`).
+//
+//      `zf::LinkMessage` only carries a payload and a timestamp, and the runtime only transmits these two between
+//      nodes. Adding an envelope around the payload in this crate would be visible to, and break, every non-Python node
+//      receiving it: attachments first have to be supported by `zf::LinkMessage` in zenoh-flow-nodes.
 #[pyo3::pyclass]
 pub struct LinkMessage {
     pub(crate) message: zf::LinkMessage,
-    pub(crate) serialised_payload: Vec<u8>,
+    pub(crate) serialised_payload: Option<Arc<Vec<u8>>>,
 }
 
 impl From<zf::LinkMessage> for LinkMessage {
     fn from(value: zf::LinkMessage) -> Self {
         LinkMessage {
             message: value,
-            serialised_payload: Vec::default(),
+            serialised_payload: None,
         }
     }
 }
 
 #[pyo3::pymethods]
 impl LinkMessage {
-    pub fn payload(&mut self) -> PyResult<&[u8]> {
-        match self.message.payload() {
-            // TODO As pointed by @gabrik, in a previous version of Zenoh-Flow the call to the
-            //      method `as_slice` was replaced with another approach because, performance-wise,
-            //      it was vastly inferior.
-            //
-            //      Given that pyO3 changed quite a bit between these versions, we ought to check
-            //      if that assumption still holds.
-            zf::Payload::Bytes(bytes) => Ok(bytes.as_slice()),
+    /// Returns a read-only `memoryview` over the payload.
+    ///
+    /// If the payload was received as bytes, no copy is performed. If it was received typed (i.e. the upstream node
+    /// runs in the same process), it is serialised once and the result is cached.
+    pub fn payload<'p>(&mut self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let bytes = match self.message.payload() {
+            zf::Payload::Bytes(bytes) => bytes.clone(),
             zf::Payload::Typed((data, serialiser)) => {
-                if self.serialised_payload.is_empty() {
-                    (serialiser)(&mut self.serialised_payload, data.clone()).map_err(|e| {
-                        ZFError::from(anyhow!("Failed to serialise payload: {e:?}"))
-                    })?;
+                // The payload was sent by a Python node running in the same process: a new view is created over its
+                // buffer, each view being released independently by its receiver.
+                if let Some(python_buffer) = data.as_any().downcast_ref::<PythonBuffer>() {
+                    let view = python_buffer.view.as_ref(py);
+                    // SAFETY: `PyMemoryView_FromObject` returns a new reference, or NULL with an exception set.
+                    return unsafe {
+                        py.from_owned_ptr_or_err(ffi::PyMemoryView_FromObject(view.as_ptr()))
+                    };
                 }
 
-                Ok(self.serialised_payload.as_slice())
+                match &self.serialised_payload {
+                    Some(bytes) => bytes.clone(),
+                    None => {
+                        let mut buffer = Vec::default();
+                        (serialiser)(&mut buffer, data.clone()).map_err(|e| {
+                            ZFError::Serialization(anyhow!("Failed to serialise payload: {e:?}"))
+                        })?;
+                        let bytes = Arc::new(buffer);
+                        self.serialised_payload = Some(bytes.clone());
+                        bytes
+                    }
+                }
             }
-        }
+        };
+
+        PayloadBuffer::view(py, bytes)
     }
 
     pub fn timestamp(&self) -> Timestamp {
@@ -182,30 +446,110 @@ impl LinkMessage {
     }
 }
 
-struct ZFError(pub(crate) anyhow::Error);
+create_exception!(
+    zenoh_flow_python,
+    ZenohFlowError,
+    PyValueError,
+    "Base class of all the errors raised by Zenoh-Flow. The `chain` attribute contains the messages of the error and of \
+     all its causes. It derives from `ValueError`, the type of the errors raised by previous versions."
+);
+create_exception!(
+    zenoh_flow_python,
+    DisconnectedError,
+    ZenohFlowError,
+    "Raised when receiving on, or sending to, a channel that is disconnected."
+);
+create_exception!(
+    zenoh_flow_python,
+    ChannelFullError,
+    ZenohFlowError,
+    "Raised by `try_send` when the message could not be sent without blocking."
+);
+create_exception!(
+    zenoh_flow_python,
+    PortNotFoundError,
+    ZenohFlowError,
+    "Raised when taking a port that does not exist or that was already taken."
+);
+create_exception!(
+    zenoh_flow_python,
+    SerializationError,
+    ZenohFlowError,
+    "Raised when a payload could not be serialised or deserialised."
+);
+
+/// The errors surfaced to Python, each variant being converted into its dedicated exception.
+pub(crate) enum ZFError {
+    Other(anyhow::Error),
+    Disconnected(anyhow::Error),
+    ChannelFull(anyhow::Error),
+    PortNotFound(anyhow::Error),
+    Serialization(anyhow::Error),
+}
+
+impl ZFError {
+    /// Classifies an error returned by `try_send`.
+    ///
+    /// Only a full channel is reported as such: a closed channel is reported as disconnected and an object that could
+    /// not be encoded, in which case the [PyErr] raised by the codec is part of the chain of causes, as a serialisation
+    /// error.
+    fn from_try_send(error: anyhow::Error) -> Self {
+        let variant: fn(anyhow::Error) -> Self = error
+            .chain()
+            .find_map(|cause| {
+                if cause.is::<PyErr>() {
+                    return Some(Self::Serialization as fn(anyhow::Error) -> Self);
+                }
+
+                match cause.downcast_ref::<flume::TrySendError<zf::LinkMessage>>()? {
+                    flume::TrySendError::Full(_) => Some(Self::ChannelFull),
+                    flume::TrySendError::Disconnected(_) => Some(Self::Disconnected),
+                }
+            })
+            .unwrap_or(Self::Other);
+
+        variant(error)
+    }
+}
 
 impl From<ZFError> for PyErr {
     fn from(value: ZFError) -> Self {
-        let message_chain = value
-            .0
-            .chain()
-            .map(|e| e.to_string())
+        let (error, new_err): (_, fn(String) -> PyErr) = match value {
+            ZFError::Other(error) => (error, ZenohFlowError::new_err),
+            ZFError::Disconnected(error) => (error, DisconnectedError::new_err),
+            ZFError::ChannelFull(error) => (error, ChannelFullError::new_err),
+            ZFError::PortNotFound(error) => (error, PortNotFoundError::new_err),
+            ZFError::Serialization(error) => (error, SerializationError::new_err),
+        };
+
+        let chain = error.chain().map(|e| e.to_string()).collect::<Vec<_>>();
+        let message_chain = chain
+            .iter()
             .fold(String::default(), |acc, e| format!("{acc}\n{e}"));
 
-        PyValueError::new_err(message_chain)
+        let py_err = new_err(message_chain);
+        Python::with_gil(|py| {
+            if let Err(e) = py_err.value(py).setattr("chain", chain) {
+                tracing::error!(
+                    "Failed to set the `chain` attribute of a Zenoh-Flow exception: {e:?}"
+                );
+            }
+        });
+
+        py_err
     }
 }
 
 impl From<anyhow::Error> for ZFError {
     fn from(value: anyhow::Error) -> Self {
-        Self(value)
+        Self::Other(value)
     }
 }
 
-/// Converts the provided [Configuration] into a [PyObject].
+/// Converts the provided [zf::Configuration] into a [PyObject].
 ///
-/// This function is required because we cannot simply wrap a [Configuration] in a new type and then expose the same
-/// methods as [serde_json::Value].
+/// This function is required because we cannot simply wrap a [zf::Configuration] in a new type and then expose the
+/// same methods as [serde_json::Value].
 pub fn configuration_into_py(
     py: Python,
     mut configuration: zf::Configuration,
@@ -227,33 +571,14 @@ pub fn configuration_into_py(
         }
         Value::Bool(b) => Ok(b.to_object(py)),
         Value::Number(n) => {
-            if n.is_i64() {
-                Ok(n.as_i64()
-                    .ok_or_else(|| {
-                        PyErr::from_value(
-                            PyTypeError::new_err(format!("Unable to convert {n:?} to i64"))
-                                .value(py),
-                        )
-                    })?
-                    .to_object(py))
-            } else if n.is_u64() {
-                Ok(n.as_u64()
-                    .ok_or_else(|| {
-                        PyErr::from_value(
-                            PyTypeError::new_err(format!("Unable to convert {n:?} to u64"))
-                                .value(py),
-                        )
-                    })?
-                    .to_object(py))
+            if let Some(n) = n.as_i64() {
+                Ok(n.to_object(py))
+            } else if let Some(n) = n.as_u64() {
+                Ok(n.to_object(py))
+            } else if let Some(n) = n.as_f64() {
+                Ok(n.to_object(py))
             } else {
-                Ok(n.as_f64()
-                    .ok_or_else(|| {
-                        PyErr::from_value(
-                            PyTypeError::new_err(format!("Unable to convert {n:?} to f64"))
-                                .value(py),
-                        )
-                    })?
-                    .to_object(py))
+                Err(ZFError::from(anyhow!("Unable to convert number < {n} > to Python")).into())
             }
         }
         Value::String(s) => Ok(s.to_object(py)),
@@ -261,6 +586,175 @@ pub fn configuration_into_py(
     }
 }
 
+/// Converts the provided Python object into a [zf::Configuration], the reverse of [configuration_into_py].
+///
+/// The conversion follows what the Zenoh-Flow runtime does when parsing a configuration from a descriptor:
+/// - integers that fit neither in an `i64` nor in a `u64` are converted to floats,
+/// - non-finite floats (i.e. NaN and infinities), which have no JSON representation, are converted to `None`.
+///
+/// `None`, booleans, integers, floats, strings, lists, tuples, dictionaries with string keys and [Configuration]s are
+/// accepted.
+pub fn py_into_configuration(object: &PyAny) -> PyResult<zf::Configuration> {
+    if object.is_none() {
+        return Ok(Value::Null.into());
+    }
+
+    if let Ok(configuration) = object.extract::<PyRef<Configuration>>() {
+        return Ok((*configuration.0).clone().into());
+    }
+
+    // NOTE: `bool` is a subclass of `int` in Python, it must be checked first.
+    if let Ok(b) = object.downcast::<PyBool>() {
+        return Ok(Value::Bool(b.is_true()).into());
+    }
+
+    if object.is_instance_of::<PyLong>() {
+        let number = match object.extract::<i64>() {
+            Ok(n) => Value::from(n),
+            Err(_) => match object.extract::<u64>() {
+                Ok(n) => Value::from(n),
+                Err(_) => float_into_value(object.extract::<f64>()?),
+            },
+        };
+        return Ok(number.into());
+    }
+
+    if let Ok(f) = object.downcast::<PyFloat>() {
+        return Ok(float_into_value(f.value()).into());
+    }
+
+    if let Ok(s) = object.downcast::<PyString>() {
+        return Ok(Value::String(s.to_str()?.to_string()).into());
+    }
+
+    if let Ok(dict) = object.downcast::<PyDict>() {
+        let mut map = serde_json::Map::with_capacity(dict.len());
+        for (key, value) in dict {
+            let key = key.downcast::<PyString>().map_err(|_| {
+                ZFError::from(anyhow!(
+                    "Configuration keys must be strings, found < {key} > of type < {} >",
+                    key.get_type().name().unwrap_or("?")
+                ))
+            })?;
+            map.insert(
+                key.to_str()?.to_string(),
+                py_into_configuration(value)?.take(),
+            );
+        }
+        return Ok(Value::Object(map).into());
+    }
+
+    if object.is_instance_of::<PyList>() || object.is_instance_of::<PyTuple>() {
+        return Ok(Value::Array(
+            object
+                .iter()?
+                .map(|item| py_into_configuration(item?).map(|mut value| value.take()))
+                .collect::<PyResult<Vec<_>>>()?,
+        )
+        .into());
+    }
+
+    Err(ZFError::from(anyhow!(
+        "Unable to convert an object of type < {} > to a Configuration",
+        object.get_type().name()?
+    ))
+    .into())
+}
+
+/// Converts a float into a [Value], mapping non-finite floats to [Value::Null] as `serde_json` does.
+fn float_into_value(f: f64) -> Value {
+    serde_json::Number::from_f64(f)
+        .map(Value::Number)
+        .unwrap_or(Value::Null)
+}
+
+/// The name of the class attribute through which a node declares the schema of its configuration.
+pub const CONFIGURATION_SCHEMA_ATTRIBUTE: &str = "configuration_schema";
+
+/// Converts the provided [zf::Configuration] into the object passed to the constructor of the node.
+///
+/// If the class of the node declares a schema, the environment variables referenced in the configuration are
+/// interpolated and converted to their declared type, the configuration is validated and an instance of the schema
+/// (with its defaults) is built. This is performed by the `zenoh_flow_python.configuration` Python module, see its
+/// documentation for the supported schemas.
+///
+/// Otherwise, the configuration is converted as is, see [configuration_into_py].
+///
+/// # Errors
+///
+/// This function will fail if the configuration could not be converted or if it does not match the schema, the error
+/// listing every mismatch with its path in the configuration.
+pub fn typed_configuration_into_py(
+    node_class: &PyAny,
+    configuration: zf::Configuration,
+) -> anyhow::Result<PyObject> {
+    let py = node_class.py();
+    let py_configuration = configuration_into_py(py, configuration)
+        .map_err(|e| anyhow!("Failed to convert `Configuration` to `PyObject`: {e:?}"))?;
+
+    let schema = match node_class.getattr(CONFIGURATION_SCHEMA_ATTRIBUTE) {
+        Ok(schema) if !schema.is_none() => schema,
+        _ => return Ok(py_configuration),
+    };
+
+    py.import("zenoh_flow_python.configuration")
+        .and_then(|module| module.call_method1("build", (schema, py_configuration)))
+        .map(|configuration| configuration.into())
+        .map_err(|e| anyhow!("Invalid configuration:\n{e}"))
+}
+
+/// The configuration of a node, exactly as the Zenoh-Flow runtime passes it to the constructor of the node.
+#[pyo3::pyclass]
+pub struct Configuration(pub(crate) zf::Configuration);
+
+impl From<zf::Configuration> for Configuration {
+    fn from(value: zf::Configuration) -> Self {
+        Self(value)
+    }
+}
+
+#[pyo3::pymethods]
+impl Configuration {
+    /// Creates a Configuration from the provided Python object, see [py_into_configuration].
+    #[new]
+    #[pyo3(signature = (configuration = None))]
+    pub fn new(configuration: Option<&PyAny>) -> PyResult<Self> {
+        match configuration {
+            Some(configuration) => py_into_configuration(configuration).map(Self),
+            None => Ok(Self(Value::Null.into())),
+        }
+    }
+
+    /// Parses a Configuration from its JSON representation.
+    #[staticmethod]
+    pub fn from_json(json: &str) -> PyResult<Self> {
+        serde_json::from_str::<Value>(json)
+            .map(|value| Self(value.into()))
+            .map_err(|e| ZFError::from(anyhow!("Invalid JSON configuration: {e:?}")).into())
+    }
+
+    /// Returns the Python objects (i.e. dictionaries, lists, strings, ...) of this Configuration.
+    pub fn to_py(&self, py: Python<'_>) -> PyResult<PyObject> {
+        configuration_into_py(py, (*self.0).clone().into())
+    }
+
+    pub fn to_json(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __richcmp__(&self, other: PyRef<Configuration>, op: CompareOp, py: Python<'_>) -> PyObject {
+        match op {
+            CompareOp::Eq => (*self.0 == *other.0).into_py(py),
+            CompareOp::Ne => (*self.0 != *other.0).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Configuration({})", *self.0)
+    }
+}
+
 #[pyo3::pyclass]
 pub struct InputRaw(pub(crate) zf::InputRaw);
 
@@ -270,30 +764,292 @@ impl From<zf::InputRaw> for InputRaw {
     }
 }
 
+impl InputRaw {
+    /// Returns an awaitable resolving to the next message or, if none was received before `timeout` expired, to
+    /// `None`.
+    fn recv_timeout<'p>(&self, py: Python<'p>, timeout: Duration) -> PyResult<&'p PyAny> {
+        let this = self.0.clone();
+        pyo3_asyncio::async_std::future_into_py(py, async move {
+            match async_std::future::timeout(timeout, this.recv()).await {
+                Ok(Ok(link_message)) => Ok(Some(LinkMessage::from(link_message))),
+                Ok(Err(e)) => Err(ZFError::Disconnected(e).into()),
+                Err(_) => Ok(None),
+            }
+        })
+    }
+}
+
 #[pyo3::pymethods]
 impl InputRaw {
-    pub fn recv_async<'p>(&'p self, py: Python<'p>) -> PyResult<&'p PyAny> {
+    #[pyo3(signature = (timeout_ms = None))]
+    pub fn recv_async<'p>(
+        &'p self,
+        py: Python<'p>,
+        timeout_ms: Option<u64>,
+    ) -> PyResult<&'p PyAny> {
+        if let Some(timeout_ms) = timeout_ms {
+            return self.recv_timeout(py, Duration::from_millis(timeout_ms));
+        }
+
         let this = self.0.clone();
         pyo3_asyncio::async_std::future_into_py(py, async move {
             match this.recv().await {
                 Ok(link_message) => Ok(LinkMessage::from(link_message)),
-                Err(e) => Err(ZFError::from(e).into()),
+                Err(e) => Err(ZFError::Disconnected(e).into()),
             }
         })
     }
 
+    /// Returns an awaitable resolving to the next message or, if none was received before the deadline, to `None`.
+    ///
+    /// The deadline is expressed as the number of milliseconds elapsed since UNIX_EPOCH. A deadline in the past
+    /// behaves as [try_recv](InputRaw::try_recv).
+    pub fn recv_until<'p>(&'p self, py: Python<'p>, deadline_ms: u64) -> PyResult<&'p PyAny> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| ZFError::from(anyhow!("System time is before UNIX_EPOCH: {e:?}")))?;
+        self.recv_timeout(py, Duration::from_millis(deadline_ms).saturating_sub(now))
+    }
+
     pub fn try_recv(&self) -> PyResult<Option<LinkMessage>> {
         match self.0.try_recv() {
             Ok(maybe_message) => Ok(maybe_message.map(LinkMessage::from)),
-            Err(e) => Err(ZFError::from(e).into()),
+            Err(e) => Err(ZFError::Disconnected(e).into()),
         }
     }
 
+    /// Returns an awaitable resolving to a list of, at most, `max` messages.
+    ///
+    /// The awaitable waits for a first message (or, if provided, until the timeout expires in which case the list is
+    /// empty) and then drains, without waiting, the messages already queued.
+    ///
+    /// NOTE: If the channel is disconnected while draining, the messages already received are returned: the error
+    ///       will be raised by the next call.
+    #[pyo3(signature = (max, timeout_ms = None))]
+    pub fn recv_many<'p>(
+        &'p self,
+        py: Python<'p>,
+        max: usize,
+        timeout_ms: Option<u64>,
+    ) -> PyResult<&'p PyAny> {
+        let this = self.0.clone();
+        pyo3_asyncio::async_std::future_into_py(py, async move {
+            let mut messages = Vec::default();
+            if max == 0 {
+                return Ok(messages);
+            }
+
+            let first_message = match timeout_ms {
+                Some(timeout_ms) => {
+                    match async_std::future::timeout(Duration::from_millis(timeout_ms), this.recv())
+                        .await
+                    {
+                        Ok(result) => result,
+                        Err(_) => return Ok(messages),
+                    }
+                }
+                None => this.recv().await,
+            };
+            messages.push(LinkMessage::from(
+                first_message.map_err(ZFError::Disconnected)?,
+            ));
+
+            while messages.len() < max {
+                match this.try_recv() {
+                    Ok(Some(link_message)) => messages.push(LinkMessage::from(link_message)),
+                    Ok(None) | Err(_) => break,
+                }
+            }
+
+            Ok(messages)
+        })
+    }
+
+    /// Blocks until a message is received or, if provided, until the timeout expires, in which case `None` is
+    /// returned.
+    ///
+    /// The GIL is released while waiting, allowing other Python threads to run.
+    #[pyo3(signature = (timeout_ms = None))]
+    pub fn recv_blocking(
+        &self,
+        py: Python<'_>,
+        timeout_ms: Option<u64>,
+    ) -> PyResult<Option<LinkMessage>> {
+        let this = self.0.clone();
+        py.allow_threads(|| match timeout_ms {
+            Some(timeout_ms) => async_std::task::block_on(async_std::future::timeout(
+                Duration::from_millis(timeout_ms),
+                this.recv(),
+            ))
+            .ok()
+            .transpose(),
+            None => async_std::task::block_on(this.recv()).map(Some),
+        })
+        .map(|maybe_message| maybe_message.map(LinkMessage::from))
+        .map_err(|e| ZFError::Disconnected(e).into())
+    }
+
+    pub fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// Returns an awaitable resolving to the next message received on the raw Input.
+    ///
+    /// NOTE: Receiving on the underlying channel can only fail if it is disconnected, which we translate into the end
+    ///       of the iteration.
+    pub fn __anext__<'p>(&'p self, py: Python<'p>) -> PyResult<Option<&'p PyAny>> {
+        let this = self.0.clone();
+        pyo3_asyncio::async_std::future_into_py(py, async move {
+            this.recv().await.map(LinkMessage::from).map_err(|e| {
+                PyStopAsyncIteration::new_err(format!(
+                    "Input < {} > is disconnected: {e:?}",
+                    this.port_id()
+                ))
+            })
+        })
+        .map(Some)
+    }
+
     pub fn port_id(&self) -> &str {
         self.0.port_id()
     }
 }
 
+/// Selects, among several raw Inputs, the first one that receives a message.
+///
+/// If `biased` is false, the Inputs are polled in a rotating order, such that a busy Input cannot starve the others.
+/// If `biased` is true, they are polled in the order in which they are provided, which therefore acts as a priority.
+///
+/// No message is lost when an Input is not selected: the messages it could have received stay in its channel.
+#[pyo3::pyclass]
+pub struct Selector {
+    inputs: Vec<zf::InputRaw>,
+    biased: bool,
+    /// Offset from which the Inputs start being polled, incremented at each call to ensure fairness.
+    offset: AtomicUsize,
+}
+
+#[pyo3::pymethods]
+impl Selector {
+    #[new]
+    #[pyo3(signature = (inputs, biased = false))]
+    pub fn new(inputs: Vec<PyRef<'_, InputRaw>>, biased: bool) -> PyResult<Self> {
+        if inputs.is_empty() {
+            return Err(ZFError::from(anyhow!("A `Selector` requires at least one Input")).into());
+        }
+
+        Ok(Self {
+            inputs: inputs.iter().map(|input| input.0.clone()).collect(),
+            biased,
+            offset: AtomicUsize::new(0),
+        })
+    }
+
+    /// Returns an awaitable resolving to the first message received on any of the Inputs, along with the port id of
+    /// the Input that received it.
+    pub fn recv_async<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let mut inputs = self.inputs.clone();
+        if !self.biased {
+            let offset = self.offset.fetch_add(1, Ordering::Relaxed) % inputs.len();
+            inputs.rotate_left(offset);
+        }
+
+        pyo3_asyncio::async_std::future_into_py(py, async move {
+            let (message, index, _) =
+                futures::future::select_all(inputs.iter().map(|input| Box::pin(input.recv())))
+                    .await;
+            let port_id = inputs[index].port_id().to_string();
+
+            match message {
+                Ok(link_message) => Ok((port_id, LinkMessage::from(link_message))),
+                Err(e) => Err(ZFError::Disconnected(
+                    e.context(format!("Input < {port_id} > is disconnected")),
+                )
+                .into()),
+            }
+        })
+    }
+}
+
+/// A typed Zenoh-Flow Input: the payloads it receives are decoded by the `codec` it was created with.
+///
+/// The `codec` can be any Python object that exposes a `decode` callable, taking a read-only `memoryview` over the
+/// bytes of the payload and returning a Python object.
+///
+/// NOTE: The Input is built on top of a raw Input, rather than on a `zf::Input`, to tell apart the errors: receiving
+///       on a raw Input only fails if the channel is disconnected, decoding failures are reported by this struct.
+#[pyo3::pyclass]
+pub struct Input {
+    pub(crate) input: zf::InputRaw,
+    pub(crate) codec: Arc<PyObject>,
+}
+
+impl Input {
+    /// Decodes the payload of the message.
+    ///
+    /// A payload sent by a typed Output of a Python node running in the same process is received as is. Any other
+    /// payload is, if needed serialised, and decoded by the codec.
+    fn decode(
+        codec: &PyObject,
+        link_message: zf::LinkMessage,
+    ) -> Result<(PyObject, Timestamp), ZFError> {
+        let timestamp = Timestamp::from(link_message.timestamp());
+
+        Python::with_gil(|py| {
+            let bytes = match link_message.payload() {
+                zf::Payload::Bytes(bytes) => bytes.clone(),
+                zf::Payload::Typed((data, serialiser)) => {
+                    if let Some(object) = data.as_any().downcast_ref::<PyObject>() {
+                        return Ok((object.clone_ref(py), timestamp));
+                    }
+
+                    let mut buffer = Vec::default();
+                    (serialiser)(&mut buffer, data.clone()).map_err(|e| {
+                        ZFError::Serialization(e.context("Failed to serialise payload"))
+                    })?;
+                    Arc::new(buffer)
+                }
+            };
+
+            // NOTE: The codec receives a view over the bytes of the payload, such that it can decode them without
+            //       copying them (e.g. the `NDArrayCodec` builds the array over that memory).
+            let payload = PayloadBuffer::view(py, bytes).map_err(|e| {
+                ZFError::Serialization(anyhow::Error::new(e).context("Failed to view the payload"))
+            })?;
+
+            codec
+                .call_method1(py, "decode", (payload,))
+                .map(|object| (object, timestamp))
+                .map_err(|e| {
+                    ZFError::Serialization(anyhow::Error::new(e).context("Call to `decode` failed"))
+                })
+        })
+    }
+}
+
+#[pyo3::pymethods]
+impl Input {
+    pub fn recv_async<'p>(&'p self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let input = self.input.clone();
+        let codec = self.codec.clone();
+        pyo3_asyncio::async_std::future_into_py(py, async move {
+            let link_message = input.recv().await.map_err(ZFError::Disconnected)?;
+            Ok(Input::decode(&codec, link_message)?)
+        })
+    }
+
+    pub fn try_recv(&self) -> PyResult<Option<(PyObject, Timestamp)>> {
+        match self.input.try_recv().map_err(ZFError::Disconnected)? {
+            Some(link_message) => Ok(Some(Input::decode(&self.codec, link_message)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn port_id(&self) -> &str {
+        self.input.port_id()
+    }
+}
+
 #[pyo3::pyclass]
 pub struct Inputs(pub(crate) zf::Inputs);
 
@@ -305,22 +1061,100 @@ impl From<zf::Inputs> for Inputs {
 
 #[pyo3::pymethods]
 impl Inputs {
+    /// Returns the ids of the ports that have not yet been taken, sorted.
+    pub fn port_ids(&self) -> Vec<String> {
+        let mut port_ids: Vec<String> = self.0.keys().map(|port_id| port_id.to_string()).collect();
+        port_ids.sort();
+        port_ids
+    }
+
+    pub fn __contains__(&self, port_id: &str) -> bool {
+        self.0.keys().any(|id| &**id == port_id)
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Takes all the remaining Inputs, as raw Inputs, indexed by their port id.
+    pub fn take_all_raw(&mut self) -> HashMap<String, InputRaw> {
+        self.port_ids()
+            .into_iter()
+            .filter_map(|port_id| {
+                let input = self.0.take(&port_id)?;
+                Some((port_id, input.raw().into()))
+            })
+            .collect()
+    }
+
     pub fn take_raw(&mut self, port_id: &str) -> PyResult<InputRaw> {
         self.0
             .take(port_id)
             .map(|input_builder| input_builder.raw().into())
             .ok_or_else(|| {
-                ZFError::from(anyhow!("Found no Input associated with port < {port_id} >")).into()
+                ZFError::PortNotFound(anyhow!("Found no Input associated with port < {port_id} >"))
+                    .into()
+            })
+    }
+
+    pub fn take(&mut self, py: Python<'_>, port_id: &str, codec: PyObject) -> PyResult<Input> {
+        if !codec.as_ref(py).hasattr("decode")? {
+            return Err(ZFError::from(anyhow!(
+                "The codec provided for Input < {port_id} > does not have a `decode` method"
+            ))
+            .into());
+        }
+
+        self.0
+            .take(port_id)
+            .map(|input_builder| Input {
+                input: input_builder.raw(),
+                codec: Arc::new(codec),
+            })
+            .ok_or_else(|| {
+                ZFError::PortNotFound(anyhow!("Found no Input associated with port < {port_id} >"))
+                    .into()
             })
     }
 }
 
+/// A raw Zenoh-Flow Output.
 #[pyo3::pyclass]
-pub struct OutputRaw(pub(crate) zf::OutputRaw);
+pub struct OutputRaw {
+    pub(crate) output: zf::OutputRaw,
+    /// Whether the payloads are sent without being copied, see [PythonBuffer::into_payload].
+    pub(crate) zero_copy: bool,
+}
 
 impl From<zf::OutputRaw> for OutputRaw {
     fn from(output: zf::OutputRaw) -> Self {
-        Self(output)
+        Self {
+            output,
+            zero_copy: false,
+        }
+    }
+}
+
+impl OutputRaw {
+    /// Sends the payload with the provided timestamp.
+    ///
+    /// A full [Timestamp] is forwarded as is, while a number of milliseconds is converted by the Zenoh-Flow runtime.
+    async fn send_with_timestamp(
+        output: &zf::OutputRaw,
+        payload: zf::Payload,
+        timestamp: Option<TimestampArg>,
+    ) -> zf::Result<()> {
+        match timestamp {
+            Some(TimestampArg::Timestamp(timestamp)) => {
+                output
+                    .forward(zf::LinkMessage::new(payload, timestamp.0))
+                    .await
+            }
+            Some(TimestampArg::Milliseconds(timestamp_ms)) => {
+                output.send(payload, Some(timestamp_ms)).await
+            }
+            None => output.send(payload, None).await,
+        }
     }
 }
 
@@ -329,24 +1163,147 @@ impl OutputRaw {
     pub fn send_async<'p>(
         &'p self,
         py: Python<'p>,
-        payload: Vec<u8>,
+        payload: &PyAny,
+        timestamp: Option<TimestampArg>,
+    ) -> PyResult<&'p PyAny> {
+        let payload = PythonBuffer::new(payload)?.into_payload(self.zero_copy);
+        let this = self.output.clone();
+        let port_id = self.output.port_id().clone();
+        pyo3_asyncio::async_std::future_into_py(py, async move {
+            OutputRaw::send_with_timestamp(&this, payload, timestamp)
+                .await
+                .map_err(|e| {
+                    ZFError::Disconnected(anyhow!("Failed to send on < {port_id} >: {e:?}")).into()
+                })
+        })
+    }
+
+    /// Returns an awaitable sending, in order, all the provided payloads.
+    ///
+    /// If provided, `timestamps` must contain as many elements as `payloads`: the i-th timestamp is associated with
+    /// the i-th payload.
+    #[pyo3(signature = (payloads, timestamps = None))]
+    pub fn send_many<'p>(
+        &'p self,
+        py: Python<'p>,
+        payloads: Vec<&PyAny>,
+        timestamps: Option<Vec<Option<TimestampArg>>>,
+    ) -> PyResult<&'p PyAny> {
+        let timestamps = match timestamps {
+            Some(timestamps) if timestamps.len() != payloads.len() => {
+                return Err(ZFError::from(anyhow!(
+                    "Expected as many timestamps as payloads, got {} timestamps for {} payloads",
+                    timestamps.len(),
+                    payloads.len()
+                ))
+                .into())
+            }
+            Some(timestamps) => timestamps,
+            None => payloads.iter().map(|_| None).collect(),
+        };
+        let payloads = payloads
+            .into_iter()
+            .map(|payload| {
+                PythonBuffer::new(payload).map(|buffer| buffer.into_payload(self.zero_copy))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+
+        let this = self.output.clone();
+        let port_id = self.output.port_id().clone();
+        pyo3_asyncio::async_std::future_into_py(py, async move {
+            for (payload, timestamp) in payloads.into_iter().zip(timestamps) {
+                OutputRaw::send_with_timestamp(&this, payload, timestamp)
+                    .await
+                    .map_err(|e| {
+                        ZFError::Disconnected(anyhow!("Failed to send on < {port_id} >: {e:?}"))
+                    })?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Blocks until the payload is sent.
+    ///
+    /// The GIL is released while waiting, allowing other Python threads to run.
+    pub fn send_blocking(
+        &self,
+        py: Python<'_>,
+        payload: &PyAny,
+        timestamp: Option<TimestampArg>,
+    ) -> PyResult<()> {
+        let payload = PythonBuffer::new(payload)?.into_payload(self.zero_copy);
+        let this = self.output.clone();
+        py.allow_threads(|| {
+            async_std::task::block_on(OutputRaw::send_with_timestamp(&this, payload, timestamp))
+        })
+        .map_err(|e| {
+            ZFError::Disconnected(anyhow!(
+                "Failed to send on < {} >: {e:?}",
+                self.output.port_id()
+            ))
+            .into()
+        })
+    }
+
+    pub fn try_send(&self, payload: &PyAny, timestamp: Option<TimestampArg>) -> PyResult<()> {
+        let payload = PythonBuffer::new(payload)?.into_payload(self.zero_copy);
+        match timestamp {
+            Some(TimestampArg::Timestamp(timestamp)) => self
+                .output
+                .try_forward(zf::LinkMessage::new(payload, timestamp.0)),
+            Some(TimestampArg::Milliseconds(timestamp_ms)) => {
+                self.output.try_send(payload, Some(timestamp_ms))
+            }
+            None => self.output.try_send(payload, None),
+        }
+        .map_err(|e| {
+            ZFError::from_try_send(e.context(format!(
+                "Call to `try_send` on < {} > failed",
+                self.output.port_id()
+            )))
+            .into()
+        })
+    }
+
+    pub fn port_id(&self) -> &str {
+        self.output.port_id()
+    }
+}
+
+/// A typed Zenoh-Flow Output: the Python objects it sends are encoded by the `codec` it was created with.
+///
+/// The `codec` can be any Python object that exposes an `encode` callable, taking a Python object and returning a
+/// bytes-like object (i.e. any object implementing the buffer protocol).
+///
+/// NOTE: Exactly as with its Rust counterpart, the encoding is performed lazily: if the receiving node is executed
+/// by the same process, the Python object is handed over as is and `encode` is never called.
+#[pyo3::pyclass]
+pub struct Output(pub(crate) Arc<zf::Output<PyObject>>);
+
+#[pyo3::pymethods]
+impl Output {
+    pub fn send_async<'p>(
+        &'p self,
+        py: Python<'p>,
+        data: PyObject,
         timestamp: Option<u64>,
     ) -> PyResult<&'p PyAny> {
         let this = self.0.clone();
         let port_id = self.0.port_id().clone();
         pyo3_asyncio::async_std::future_into_py(py, async move {
-            this.send(payload, timestamp).await.map_err(|e| {
-                ZFError::from(anyhow!("Failed to send on < {port_id} >: {e:?}")).into()
+            this.send(data, timestamp).await.map_err(|e| {
+                ZFError::Disconnected(anyhow!("Failed to send on < {port_id} >: {e:?}")).into()
             })
         })
     }
 
-    pub fn try_send(&self, payload: &[u8], timestamp: Option<u64>) -> PyResult<()> {
-        self.0.try_send(payload, timestamp).map_err(|e| {
-            ZFError::from(anyhow!(
-                "Call to `try_send` on < {} > failed with: {e:?}",
+    pub fn try_send(&self, data: PyObject, timestamp: Option<u64>) -> PyResult<()> {
+        self.0.try_send(data, timestamp).map_err(|e| {
+            ZFError::from_try_send(e.context(format!(
+                "Call to `try_send` on < {} > failed",
                 self.0.port_id()
-            ))
+            )))
             .into()
         })
     }
@@ -367,15 +1324,227 @@ impl From<zf::Outputs> for Outputs {
 
 #[pyo3::pymethods]
 impl Outputs {
-    pub fn take_raw(&mut self, port_id: &str) -> PyResult<OutputRaw> {
+    /// Returns the ids of the ports that have not yet been taken, sorted.
+    pub fn port_ids(&self) -> Vec<String> {
+        let mut port_ids: Vec<String> = self.0.keys().map(|port_id| port_id.to_string()).collect();
+        port_ids.sort();
+        port_ids
+    }
+
+    pub fn __contains__(&self, port_id: &str) -> bool {
+        self.0.keys().any(|id| &**id == port_id)
+    }
+
+    pub fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Takes all the remaining Outputs, as raw Outputs, indexed by their port id.
+    #[pyo3(signature = (zero_copy = false))]
+    pub fn take_all_raw(&mut self, zero_copy: bool) -> HashMap<String, OutputRaw> {
+        self.port_ids()
+            .into_iter()
+            .filter_map(|port_id| {
+                let output = self.0.take(&port_id)?;
+                Some((
+                    port_id,
+                    OutputRaw {
+                        output: output.raw(),
+                        zero_copy,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    #[pyo3(signature = (port_id, zero_copy = false))]
+    pub fn take_raw(&mut self, port_id: &str, zero_copy: bool) -> PyResult<OutputRaw> {
         self.0
             .take(port_id)
-            .map(|output_builder| output_builder.raw().into())
+            .map(|output_builder| OutputRaw {
+                output: output_builder.raw(),
+                zero_copy,
+            })
             .ok_or_else(|| {
-                ZFError::from(anyhow!(
-                    "Found not Output associated with port < {port_id} >"
+                ZFError::PortNotFound(anyhow!(
+                    "Found no Output associated with port < {port_id} >"
                 ))
                 .into()
             })
     }
+
+    pub fn take(&mut self, py: Python<'_>, port_id: &str, codec: PyObject) -> PyResult<Output> {
+        if !codec.as_ref(py).hasattr("encode")? {
+            return Err(ZFError::from(anyhow!(
+                "The codec provided for Output < {port_id} > does not have an `encode` method"
+            ))
+            .into());
+        }
+
+        self.0
+            .take(port_id)
+            .map(|output_builder| {
+                Output(Arc::new(output_builder.typed(
+                    move |buffer: &mut Vec<u8>, data: &PyObject| {
+                        Python::with_gil(|py| {
+                            let encoded =
+                                codec.call_method1(py, "encode", (data,)).map_err(|e| {
+                                    anyhow::Error::new(e).context("Call to `encode` failed")
+                                })?;
+                            let encoded = PythonBuffer::new(encoded.as_ref(py)).map_err(|e| {
+                                anyhow::Error::new(e)
+                                    .context("`encode` must return a bytes-like object")
+                            })?;
+                            buffer.extend_from_slice(encoded.as_slice());
+                            Ok(())
+                        })
+                    },
+                )))
+            })
+            .ok_or_else(|| {
+                ZFError::PortNotFound(anyhow!(
+                    "Found no Output associated with port < {port_id} >"
+                ))
+                .into()
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "1";
+
+    fn datetime<'p>(py: Python<'p>, iso: &str) -> &'p PyAny {
+        py.import("datetime")
+            .and_then(|datetime| datetime.getattr("datetime"))
+            .and_then(|datetime| datetime.call_method1("fromisoformat", (iso,)))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_timestamp_from_datetime_bounds() {
+        Python::with_gil(|py| {
+            let epoch =
+                Timestamp::from_datetime(datetime(py, "1970-01-01T00:00:00+00:00"), ID).unwrap();
+            assert_eq!(epoch.ntp64(), 0);
+
+            let last =
+                Timestamp::from_datetime(datetime(py, "2106-02-07T06:28:15+00:00"), ID).unwrap();
+            assert_eq!(last.ntp64() >> 32, u64::from(u32::MAX));
+
+            for iso in [
+                "1969-12-31T23:59:59+00:00",
+                "2106-02-07T06:28:16+00:00",
+                "9999-12-31T00:00:00+00:00",
+            ] {
+                let Err(error) = Timestamp::from_datetime(datetime(py, iso), ID) else {
+                    panic!("{iso}: expected an error");
+                };
+                assert!(error.is_instance_of::<ZenohFlowError>(py), "{iso}: {error}");
+            }
+        });
+    }
+
+    fn into_value(py: Python<'_>, expression: &str) -> PyResult<Value> {
+        py_into_configuration(py.eval(expression, None, None).unwrap())
+            .map(|mut configuration| configuration.take())
+    }
+
+    #[test]
+    fn test_py_into_configuration_numbers() {
+        Python::with_gil(|py| {
+            assert_eq!(into_value(py, "-2**63").unwrap(), Value::from(i64::MIN));
+            assert_eq!(into_value(py, "2**64 - 1").unwrap(), Value::from(u64::MAX));
+            assert_eq!(into_value(py, "2**64").unwrap(), Value::from(2f64.powi(64)));
+            assert_eq!(
+                into_value(py, "-2**63 - 1").unwrap(),
+                Value::from(-(2f64.powi(63)))
+            );
+            assert_eq!(into_value(py, "True").unwrap(), Value::Bool(true));
+            assert_eq!(into_value(py, "0.5").unwrap(), Value::from(0.5));
+            for expression in ["float('nan')", "float('inf')", "float('-inf')"] {
+                assert_eq!(
+                    into_value(py, expression).unwrap(),
+                    Value::Null,
+                    "{expression}"
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn test_py_into_configuration_containers() {
+        Python::with_gil(|py| {
+            assert_eq!(
+                into_value(py, "{'a': [1, None, (2, 'b')], 'c': {'d': float('nan')}}").unwrap(),
+                serde_json::json!({"a": [1, null, [2, "b"]], "c": {"d": null}})
+            );
+
+            for expression in ["{1: 'a'}", "{'a': {(1, 2): 'b'}}", "{'a': object()}"] {
+                let error = into_value(py, expression).unwrap_err();
+                assert!(
+                    error.is_instance_of::<ZenohFlowError>(py),
+                    "{expression}: {error}"
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn test_configuration_round_trip() {
+        Python::with_gil(|py| {
+            let value = serde_json::json!({
+                "min": i64::MIN,
+                "max": u64::MAX,
+                "ratio": 0.25,
+                "list": [true, null, "a"],
+            });
+            let object = configuration_into_py(py, value.clone().into()).unwrap();
+            assert_eq!(
+                py_into_configuration(object.as_ref(py)).unwrap().take(),
+                value
+            );
+        });
+    }
+
+    #[test]
+    fn test_try_send_errors() {
+        let message = Python::with_gil(|py| {
+            let timestamp =
+                Timestamp::from_datetime(datetime(py, "2024-01-01T00:00:00+00:00"), ID).unwrap();
+            zf::LinkMessage::new(zf::Payload::Bytes(Arc::new(Vec::default())), timestamp.0)
+        });
+
+        let (sender, receiver) = flume::bounded(1);
+        sender.try_send(message.clone()).unwrap();
+        let full = sender.try_send(message.clone()).unwrap_err();
+        assert!(matches!(
+            ZFError::from_try_send(anyhow::Error::new(full).context("port")),
+            ZFError::ChannelFull(_)
+        ));
+
+        drop(receiver);
+        let disconnected = sender.try_send(message).unwrap_err();
+        assert!(matches!(
+            ZFError::from_try_send(anyhow::Error::new(disconnected).context("port")),
+            ZFError::Disconnected(_)
+        ));
+
+        let encode = Python::with_gil(|py| {
+            py.eval("1 / 0", None, None)
+                .map_err(|e| anyhow::Error::new(e).context("Call to `encode` failed"))
+                .unwrap_err()
+        });
+        assert!(matches!(
+            ZFError::from_try_send(encode),
+            ZFError::Serialization(_)
+        ));
+
+        assert!(matches!(
+            ZFError::from_try_send(anyhow!("Unknown failure")),
+            ZFError::Other(_)
+        ));
+    }
 }
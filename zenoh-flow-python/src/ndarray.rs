@@ -0,0 +1,374 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Support for exchanging NumPy `ndarray`s.
+//!
+//! # Wire layout
+//!
+//! An ndarray is sent as a header followed by the raw bytes of the array. All integers are little-endian.
+//!
+//! | offset            | size          | field                                                             |
+//! |-------------------|---------------|-------------------------------------------------------------------|
+//! | 0                 | 4             | magic: `b"ZFND"`                                                  |
+//! | 4                 | 1             | version of the layout, currently `1`                              |
+//! | 5                 | 1             | number of dimensions, `N`                                         |
+//! | 6                 | 2             | length of the dtype, `D` (u16)                                    |
+//! | 8                 | `D`           | dtype, as its NumPy array-protocol type string (e.g. `"<f8"`)     |
+//! | 8 + `D`           | 8 * `N`       | shape, one u64 per dimension                                      |
+//! | 8 + `D` + 8 * `N` | 8 * `N`       | strides in bytes, one i64 per dimension                           |
+//! | ...               | ...           | zeroes, up to the next multiple of 16                             |
+//! | data offset       | until the end | the bytes of the array, laid out as described by shape & strides  |
+//!
+//! The data always starts at an offset that is a multiple of 16 such that, if the payload itself is aligned, so is
+//! the array.
+
+use anyhow::{anyhow, bail};
+use pyo3::{
+    prelude::*,
+    types::{PyBytes, PyDict},
+};
+
+use crate::{PythonBuffer, ZFError};
+
+const NDARRAY_MAGIC: &[u8; 4] = b"ZFND";
+const NDARRAY_VERSION: u8 = 1;
+const NDARRAY_ALIGNMENT: usize = 16;
+
+/// The metadata describing how to interpret the bytes of an ndarray.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NDArrayHeader {
+    /// The NumPy array-protocol type string of the elements, e.g. `"<f8"` for little-endian 64-bit floats.
+    pub dtype: String,
+    pub shape: Vec<u64>,
+    /// The number of bytes to skip, in each dimension, to go to the next element.
+    pub strides: Vec<i64>,
+}
+
+impl NDArrayHeader {
+    fn header_len(&self) -> usize {
+        8 + self.dtype.len() + 16 * self.shape.len()
+    }
+
+    /// Returns the offset, in bytes, at which the data of the array starts.
+    pub fn data_offset(&self) -> usize {
+        let header_len = self.header_len();
+        header_len + (NDARRAY_ALIGNMENT - header_len % NDARRAY_ALIGNMENT) % NDARRAY_ALIGNMENT
+    }
+
+    /// Encodes this header, followed by the provided `data`, into `buffer`.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if the header could not be encoded, see [encode_header](NDArrayHeader::encode_header).
+    pub fn encode_into(&self, data: &[u8], buffer: &mut Vec<u8>) -> anyhow::Result<()> {
+        let header = self.encode_header()?;
+        buffer.reserve(header.len() + data.len());
+        buffer.extend_from_slice(&header);
+        buffer.extend_from_slice(data);
+        Ok(())
+    }
+
+    /// Encodes this header, padded with zeroes up to the [data offset](NDArrayHeader::data_offset).
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if the dtype or the number of dimensions are too large to be represented, or if the
+    /// shape and strides do not have the same number of dimensions.
+    pub fn encode_header(&self) -> anyhow::Result<Vec<u8>> {
+        let ndim = u8::try_from(self.shape.len())
+            .map_err(|_| anyhow!("Too many dimensions: {}", self.shape.len()))?;
+        let dtype_len = u16::try_from(self.dtype.len())
+            .map_err(|_| anyhow!("dtype is too long: {}", self.dtype))?;
+        if self.strides.len() != self.shape.len() {
+            bail!(
+                "Shape and strides have different dimensions: {} != {}",
+                self.shape.len(),
+                self.strides.len()
+            );
+        }
+
+        let data_offset = self.data_offset();
+        let mut buffer = Vec::with_capacity(data_offset);
+        buffer.extend_from_slice(NDARRAY_MAGIC);
+        buffer.push(NDARRAY_VERSION);
+        buffer.push(ndim);
+        buffer.extend_from_slice(&dtype_len.to_le_bytes());
+        buffer.extend_from_slice(self.dtype.as_bytes());
+        self.shape
+            .iter()
+            .for_each(|dim| buffer.extend_from_slice(&dim.to_le_bytes()));
+        self.strides
+            .iter()
+            .for_each(|stride| buffer.extend_from_slice(&stride.to_le_bytes()));
+        buffer.resize(data_offset, 0);
+
+        Ok(buffer)
+    }
+
+    /// Decodes the header at the start of `payload`, returning it with the bytes of the array.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if `payload` does not start with a valid header.
+    pub fn decode(payload: &[u8]) -> anyhow::Result<(Self, &[u8])> {
+        let take = |offset: usize, len: usize| {
+            payload.get(offset..offset + len).ok_or_else(|| {
+                anyhow!(
+                    "Truncated ndarray header: expected at least {} bytes, got {}",
+                    offset + len,
+                    payload.len()
+                )
+            })
+        };
+
+        if take(0, 4)? != NDARRAY_MAGIC {
+            bail!("Not an ndarray payload: invalid magic");
+        }
+        let version = take(4, 1)?[0];
+        if version != NDARRAY_VERSION {
+            bail!("Unsupported ndarray layout version: {version}");
+        }
+        let ndim = take(5, 1)?[0] as usize;
+        let dtype_len = u16::from_le_bytes(take(6, 2)?.try_into()?) as usize;
+        let dtype = std::str::from_utf8(take(8, dtype_len)?)
+            .map_err(|e| anyhow!("Invalid ndarray dtype: {e:?}"))?
+            .to_string();
+
+        let mut offset = 8 + dtype_len;
+        let mut shape = Vec::with_capacity(ndim);
+        for _ in 0..ndim {
+            let bytes = take(offset, 8)?;
+            shape.push(u64::from_le_bytes(bytes.try_into()?));
+            offset += 8;
+        }
+        let mut strides = Vec::with_capacity(ndim);
+        for _ in 0..ndim {
+            let bytes = take(offset, 8)?;
+            strides.push(i64::from_le_bytes(bytes.try_into()?));
+            offset += 8;
+        }
+
+        let header = Self {
+            dtype,
+            shape,
+            strides,
+        };
+        let data = payload.get(header.data_offset()..).ok_or_else(|| {
+            anyhow!(
+                "Truncated ndarray payload: data should start at byte {}, got {} bytes",
+                header.data_offset(),
+                payload.len()
+            )
+        })?;
+
+        Ok((header, data))
+    }
+}
+
+/// A codec, usable with `Inputs.take` and `Outputs.take`, exchanging NumPy `ndarray`s.
+///
+/// See the documentation of this module for the wire layout.
+#[pyo3::pyclass]
+#[derive(Default)]
+pub struct NDArrayCodec;
+
+#[pyo3::pymethods]
+impl NDArrayCodec {
+    #[new]
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Encodes the provided ndarray, with its dtype, shape and strides.
+    ///
+    /// Arrays that are neither C- nor Fortran-contiguous (e.g. strided views such as `a[::2]`) are first copied into a
+    /// C-contiguous array.
+    ///
+    /// Arrays with a structured, sub-array or object dtype are rejected: the type string of the former two does not
+    /// describe their fields (e.g. `"|V12"`) and the elements of the latter are pointers to Python objects.
+    pub fn encode<'p>(&self, py: Python<'p>, array: &'p PyAny) -> PyResult<&'p PyBytes> {
+        let dtype = array.getattr("dtype")?;
+        if dtype.getattr("hasobject")?.is_true()? {
+            return Err(ZFError::Serialization(anyhow!(
+                "Cannot encode an ndarray with an object dtype ({}): its elements are Python objects",
+                dtype.str()?
+            ))
+            .into());
+        }
+        if !dtype.getattr("names")?.is_none() || !dtype.getattr("subdtype")?.is_none() {
+            return Err(ZFError::Serialization(anyhow!(
+                "Cannot encode an ndarray with a structured or sub-array dtype ({}): only plain dtypes are supported",
+                dtype.str()?
+            ))
+            .into());
+        }
+
+        let numpy = py.import("numpy")?;
+        let flags = array.getattr("flags")?;
+        let array = if flags.getattr("c_contiguous")?.is_true()?
+            || flags.getattr("f_contiguous")?.is_true()?
+        {
+            array
+        } else {
+            numpy.call_method1("ascontiguousarray", (array,))?
+        };
+
+        let header = NDArrayHeader {
+            dtype: array.getattr("dtype")?.getattr("str")?.extract()?,
+            shape: array.getattr("shape")?.extract()?,
+            strides: array.getattr("strides")?.extract()?,
+        };
+        let header_bytes = header.encode_header().map_err(ZFError::Serialization)?;
+
+        // NOTE: An empty array has no bytes, and the buffer protocol rejects the multi-dimensional ones.
+        if array.getattr("size")?.extract::<usize>()? == 0 {
+            return Ok(PyBytes::new(py, &header_bytes));
+        }
+
+        // NOTE: The bytes are read through a flat `uint8` view of the array: unlike the array itself, it exports a
+        // buffer whatever its dtype (e.g. `datetime64`). The transpose of a Fortran-contiguous array is C-contiguous
+        // and shares its memory, which lets us read its bytes as they are laid out.
+        let array = if array.getattr("flags")?.getattr("c_contiguous")?.is_true()? {
+            array
+        } else {
+            array.getattr("T")?
+        };
+        let data = PythonBuffer::new(
+            array
+                .call_method1("reshape", (-1,))?
+                .call_method1("view", (numpy.getattr("uint8")?,))?,
+        )?;
+        let data = data.as_slice();
+
+        PyBytes::new_with(py, header_bytes.len() + data.len(), |bytes| {
+            bytes[..header_bytes.len()].copy_from_slice(&header_bytes);
+            bytes[header_bytes.len()..].copy_from_slice(data);
+            Ok(())
+        })
+    }
+
+    /// Rebuilds an ndarray from the provided payload.
+    ///
+    /// The payload can be any object implementing the buffer protocol. The returned (read-only) array does not copy
+    /// the data: it is built over the memory of the payload, which it keeps alive.
+    pub fn decode<'p>(&self, py: Python<'p>, payload: &'p PyAny) -> PyResult<&'p PyAny> {
+        let buffer = PythonBuffer::new(payload)?;
+        let (header, _) =
+            NDArrayHeader::decode(buffer.as_slice()).map_err(ZFError::Serialization)?;
+
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("buffer", buffer.view.as_ref(py))?;
+        kwargs.set_item("offset", header.data_offset())?;
+        kwargs.set_item("strides", header.strides)?;
+
+        py.import("numpy")?
+            .getattr("ndarray")?
+            .call((header.shape, header.dtype), Some(kwargs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(dtype: &str, shape: Vec<u64>, strides: Vec<i64>) -> NDArrayHeader {
+        NDArrayHeader {
+            dtype: dtype.to_string(),
+            shape,
+            strides,
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let headers = [
+            header("<f8", vec![], vec![]),
+            header("<f8", vec![3], vec![8]),
+            header("|u1", vec![2, 3], vec![3, 1]),
+            header("<i4", vec![2, 3], vec![4, 8]),
+            header(">u2", vec![4, 1, 2], vec![-4, 2, 2]),
+            header("<M8[ns]", vec![5], vec![8]),
+        ];
+
+        for header in headers {
+            let data: Vec<u8> = (0..header.shape.iter().product::<u64>() as u8 * 8).collect();
+            let mut buffer = Vec::default();
+            header.encode_into(&data, &mut buffer).unwrap();
+
+            assert_eq!(buffer.len(), header.data_offset() + data.len());
+            let (decoded, decoded_data) = NDArrayHeader::decode(&buffer).unwrap();
+            assert_eq!(decoded, header);
+            assert_eq!(decoded_data, data.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_layout() {
+        let header = header("<f8", vec![2, 3], vec![24, 8]);
+        let mut buffer = Vec::default();
+        header.encode_into(&[0xff; 48], &mut buffer).unwrap();
+
+        assert_eq!(&buffer[0..4], NDARRAY_MAGIC);
+        assert_eq!(buffer[4], NDARRAY_VERSION);
+        assert_eq!(buffer[5], 2);
+        assert_eq!(&buffer[6..8], &3u16.to_le_bytes());
+        assert_eq!(&buffer[8..11], b"<f8");
+        assert_eq!(&buffer[11..19], &2u64.to_le_bytes());
+        assert_eq!(&buffer[19..27], &3u64.to_le_bytes());
+        assert_eq!(&buffer[27..35], &24i64.to_le_bytes());
+        assert_eq!(&buffer[35..43], &8i64.to_le_bytes());
+        // 43 bytes of header, padded with zeroes up to the next multiple of 16.
+        assert_eq!(header.data_offset(), 48);
+        assert_eq!(&buffer[43..48], &[0; 5]);
+        assert_eq!(&buffer[48..], &[0xff; 48]);
+    }
+
+    #[test]
+    fn test_alignment() {
+        for dtype_len in 0..32 {
+            let header = header(&"x".repeat(dtype_len), vec![1], vec![1]);
+            let header_len = 8 + dtype_len + 16;
+
+            assert_eq!(header.data_offset() % NDARRAY_ALIGNMENT, 0);
+            assert!(header.data_offset() >= header_len);
+            assert!(header.data_offset() < header_len + NDARRAY_ALIGNMENT);
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        let mut buffer = Vec::default();
+        header("<f8", vec![2], vec![8])
+            .encode_into(&[0; 16], &mut buffer)
+            .unwrap();
+
+        assert!(NDArrayHeader::decode(&buffer[..20]).is_err());
+        assert!(NDArrayHeader::decode(
+            &buffer[..header("<f8", vec![2], vec![8]).data_offset() - 1]
+        )
+        .is_err());
+
+        let mut invalid_magic = buffer.clone();
+        invalid_magic[0] = b'X';
+        assert!(NDArrayHeader::decode(&invalid_magic).is_err());
+
+        let mut invalid_version = buffer.clone();
+        invalid_version[4] = NDARRAY_VERSION + 1;
+        assert!(NDArrayHeader::decode(&invalid_version).is_err());
+
+        assert!(header("<f8", vec![2], vec![])
+            .encode_into(&[], &mut Vec::default())
+            .is_err());
+    }
+}
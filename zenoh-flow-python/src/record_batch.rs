@@ -0,0 +1,160 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! Support for exchanging Apache Arrow record batches.
+//!
+//! A record batch is sent in the Arrow IPC *streaming* format: the schema, followed by a single record batch and the
+//! end-of-stream marker. Rust nodes can read and write such payloads with `record_batch_from_ipc` and
+//! `record_batch_to_ipc`, provided the `arrow` feature of this crate is enabled.
+
+use pyo3::prelude::*;
+
+/// A codec, usable with `Inputs.take` and `Outputs.take`, exchanging Apache Arrow record batches.
+///
+/// `pyarrow.RecordBatch`es and `pandas.DataFrame`s can be encoded. Decoding returns a `pyarrow.RecordBatch` or, if
+/// the codec was created with `as_pandas=True`, a `pandas.DataFrame`.
+#[pyo3::pyclass]
+pub struct RecordBatchCodec {
+    as_pandas: bool,
+}
+
+#[pyo3::pymethods]
+impl RecordBatchCodec {
+    #[new]
+    #[pyo3(signature = (as_pandas = false))]
+    pub fn new(as_pandas: bool) -> Self {
+        Self { as_pandas }
+    }
+
+    /// Encodes the provided record batch, or DataFrame, in the Arrow IPC streaming format.
+    ///
+    /// The returned `pyarrow.Buffer` implements the buffer protocol and can thus be sent, without copy, on an
+    /// `OutputRaw`.
+    pub fn encode<'p>(&self, py: Python<'p>, data: &'p PyAny) -> PyResult<&'p PyAny> {
+        let pyarrow = py.import("pyarrow")?;
+        let record_batch_class = pyarrow.getattr("RecordBatch")?;
+        let record_batch = if data.is_instance(record_batch_class)? {
+            data
+        } else {
+            record_batch_class.call_method1("from_pandas", (data,))?
+        };
+
+        let sink = pyarrow.call_method0("BufferOutputStream")?;
+        let writer = py
+            .import("pyarrow.ipc")?
+            .call_method1("new_stream", (sink, record_batch.getattr("schema")?))?;
+        writer.call_method1("write_batch", (record_batch,))?;
+        writer.call_method0("close")?;
+
+        sink.call_method0("getvalue")
+    }
+
+    /// Decodes the record batch contained in the provided payload.
+    ///
+    /// The payload can be any object implementing the buffer protocol. When decoding to a `pyarrow.RecordBatch`, the
+    /// columns are not copied: they are built over the memory of the payload.
+    pub fn decode<'p>(&self, py: Python<'p>, payload: &'p PyAny) -> PyResult<&'p PyAny> {
+        let buffer = py
+            .import("pyarrow")?
+            .call_method1("py_buffer", (payload,))?;
+        let record_batch = py
+            .import("pyarrow.ipc")?
+            .call_method1("open_stream", (buffer,))?
+            .call_method0("read_next_batch")?;
+
+        if self.as_pandas {
+            record_batch.call_method0("to_pandas")
+        } else {
+            Ok(record_batch)
+        }
+    }
+}
+
+/// Reads the record batch contained in the provided bytes, encoded in the Arrow IPC streaming format.
+///
+/// # Errors
+///
+/// This function will fail if the bytes are not a valid Arrow IPC stream or if the stream contains no record batch.
+#[cfg(feature = "arrow")]
+pub fn record_batch_from_ipc(bytes: &[u8]) -> anyhow::Result<arrow_array::RecordBatch> {
+    let mut reader = arrow_ipc::reader::StreamReader::try_new(bytes, None)?;
+    match reader.next() {
+        Some(record_batch) => Ok(record_batch?),
+        None => anyhow::bail!("The Arrow IPC stream contains no record batch"),
+    }
+}
+
+/// Writes the provided record batch in the Arrow IPC streaming format, such that it can be decoded by a
+/// `RecordBatchCodec`.
+///
+/// # Errors
+///
+/// This function will fail if the record batch could not be written.
+#[cfg(feature = "arrow")]
+pub fn record_batch_to_ipc(record_batch: &arrow_array::RecordBatch) -> anyhow::Result<Vec<u8>> {
+    let mut writer =
+        arrow_ipc::writer::StreamWriter::try_new(Vec::default(), record_batch.schema().as_ref())?;
+    writer.write(record_batch)?;
+    writer.finish()?;
+    Ok(writer.into_inner()?)
+}
+
+#[cfg(all(test, feature = "arrow"))]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{ArrayRef, Float64Array, Int32Array, RecordBatch, StringArray};
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let record_batch = RecordBatch::try_from_iter([
+            ("id", Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef),
+            (
+                "name",
+                Arc::new(StringArray::from(vec![Some("a"), None, Some("c")])) as ArrayRef,
+            ),
+            (
+                "value",
+                Arc::new(Float64Array::from(vec![0.5, 1.5, -2.0])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+
+        let bytes = record_batch_to_ipc(&record_batch).unwrap();
+        assert_eq!(record_batch_from_ipc(&bytes).unwrap(), record_batch);
+    }
+
+    #[test]
+    fn test_invalid_stream() {
+        assert!(record_batch_from_ipc(b"not an arrow stream").is_err());
+
+        // A stream with a schema but without any record batch.
+        let record_batch =
+            RecordBatch::try_from_iter([("id", Arc::new(Int32Array::from(vec![1])) as ArrayRef)])
+                .unwrap();
+        let mut writer = arrow_ipc::writer::StreamWriter::try_new(
+            Vec::default(),
+            record_batch.schema().as_ref(),
+        )
+        .unwrap();
+        writer.finish().unwrap();
+        let bytes = writer.into_inner().unwrap();
+        assert_eq!(
+            record_batch_from_ipc(&bytes).unwrap_err().to_string(),
+            "The Arrow IPC stream contains no record batch"
+        );
+    }
+}
@@ -0,0 +1,741 @@
+//
+// Copyright (c) 2022 ZettaScale Technology
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+//
+
+//! The logic shared by the Zenoh-Flow Python wrappers: loading a node, driving its iterations and updating its
+//! configuration while it runs.
+//!
+//! The wrappers are configured through a section, reserved to them, of the configuration of each node:
+//!
+//! ```yaml
+//! configuration:
+//!   threshold: 0.5
+//!   zenoh_flow_python:
+//!     module: my_package.nodes
+//!     register: make_node
+//!     venv: /opt/my-flow/venv
+//!     sys_path:
+//!       - /opt/my-flow/common
+//!     configuration_file: /etc/my-flow/my-node.yaml
+//! ```
+//!
+//! This section is removed from the configuration before it is passed to the node.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread::JoinHandle,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{anyhow, bail};
+use pyo3::{
+    prelude::*,
+    types::{IntoPyDict, PyList, PyTuple},
+};
+use serde::Deserialize;
+use zenoh_flow_nodes::prelude as zf;
+
+use crate::{py_into_configuration, typed_configuration_into_py, Context, Inputs, Outputs};
+
+/// The key, in the configuration of a node, of the section reserved to the Zenoh-Flow Python wrappers.
+pub const WRAPPER_CONFIGURATION_KEY: &str = "zenoh_flow_python";
+
+const DEFAULT_CONFIGURATION_POLL_INTERVAL_MS: u64 = 1_000;
+
+/// The settings of the Zenoh-Flow Python wrappers for a node.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WrapperConfiguration {
+    /// The module, or `module:Class` spec, from which the node is loaded instead of its library file. It is imported
+    /// from the `sys.path` of the interpreter.
+    pub module: Option<String>,
+    /// The zip archive, holding a package, from which the node is loaded instead of its library file.
+    pub archive: Option<PathBuf>,
+    /// The name of the entry point, of the `zenoh_flow.nodes` group, from which the node is loaded instead of its
+    /// library file.
+    pub entry_point: Option<String>,
+    /// The attribute of the module of the node through which its class is obtained, instead of the classes registered
+    /// with a decorator or the `register` function.
+    pub register: Option<String>,
+    /// The virtual environment whose site-packages are added to the `sys.path` of the interpreter. Defaults to the
+    /// one set in `$VIRTUAL_ENV`, if any.
+    pub venv: Option<PathBuf>,
+    /// Entries added, in order, at the beginning of the `sys.path` of the interpreter.
+    #[serde(default)]
+    pub sys_path: Vec<PathBuf>,
+    /// A JSON or YAML file, watched for changes, from which updates of the configuration are read. Reading a YAML file
+    /// requires the PyYAML package.
+    pub configuration_file: Option<PathBuf>,
+    /// The interval, in milliseconds, at which the `configuration_file` is checked for changes.
+    pub configuration_poll_interval_ms: Option<u64>,
+}
+
+impl WrapperConfiguration {
+    /// Removes the section reserved to the wrappers from the configuration and parses it.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if the section is not valid.
+    pub fn extract(configuration: &mut zf::Configuration) -> anyhow::Result<Self> {
+        let section = match configuration.as_object_mut() {
+            Some(configuration) => configuration.remove(WRAPPER_CONFIGURATION_KEY),
+            None => None,
+        };
+
+        match section {
+            Some(section) => serde_json::from_value(section)
+                .map_err(|e| anyhow!("Invalid `{WRAPPER_CONFIGURATION_KEY}` section: {e:?}")),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Adds the `sys_path` entries and the site-packages of the virtual environment at the beginning of the `sys.path`
+    /// of the interpreter, in that order.
+    ///
+    /// The `.pth` files of the site-packages are processed, as for any site directory. As there is a single interpreter
+    /// per process, the entries are shared by all the Python nodes of the process: the first node importing a module
+    /// determines which version of it is used.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if the configured virtual environment has no site-packages folder.
+    pub fn prepare_environment(&self, py: Python<'_>) -> anyhow::Result<()> {
+        let sys_path: &PyList = py
+            .import("sys")
+            .and_then(|sys| sys.getattr("path"))
+            .map_err(|e| anyhow!("Failed to access `sys.path`: {e:?}"))?
+            .downcast()
+            .map_err(|e| anyhow!("`sys.path` is not a list: {e:?}"))?;
+
+        let mut entries = Vec::with_capacity(self.sys_path.len());
+        for path in self.sys_path.iter() {
+            if !sys_path.contains(path)? {
+                entries.push(path.into_py(py));
+            }
+        }
+
+        let venv = match &self.venv {
+            Some(venv) => Some((venv.clone(), true)),
+            None => std::env::var_os("VIRTUAL_ENV").map(|venv| (PathBuf::from(venv), false)),
+        };
+        if let Some((venv, configured)) = venv {
+            let version_info = py.version_info();
+            let site_packages = if cfg!(windows) {
+                venv.join("Lib").join("site-packages")
+            } else {
+                venv.join("lib")
+                    .join(format!(
+                        "python{}.{}",
+                        version_info.major, version_info.minor
+                    ))
+                    .join("site-packages")
+            };
+
+            if site_packages.is_dir() {
+                tracing::debug!(
+                    "Adding the site-packages of the virtual environment to `sys.path`: {}",
+                    site_packages.display()
+                );
+                // NOTE: `addsitedir` appends the site-packages, and the entries of its `.pth` files, to `sys.path`: we
+                // move them to the beginning so that they take precedence over the packages of the interpreter.
+                let known = sys_path.len();
+                py.import("site")
+                    .and_then(|site| site.call_method1("addsitedir", (&site_packages,)))
+                    .map_err(|e| {
+                        anyhow!(
+                            "Failed to add < {} > to `sys.path`: {e:?}",
+                            site_packages.display()
+                        )
+                    })?;
+                entries.extend(
+                    sys_path
+                        .get_slice(known, sys_path.len())
+                        .iter()
+                        .map(Into::into),
+                );
+                sys_path.del_slice(known, sys_path.len())?;
+            } else if configured {
+                bail!(
+                    "The virtual environment < {} > has no site-packages folder < {} >",
+                    venv.display(),
+                    site_packages.display()
+                );
+            } else {
+                tracing::warn!(
+                    "Ignoring `$VIRTUAL_ENV`: there is no site-packages folder < {} >",
+                    site_packages.display()
+                );
+            }
+        }
+
+        for (index, entry) in entries.into_iter().enumerate() {
+            sys_path.insert(index, entry)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns a [ConfigurationWatcher] if a `configuration_file` was provided.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if the format of the `configuration_file` is not supported, see [ConfigurationWatcher::new].
+    pub fn configuration_watcher(
+        &self,
+        py: Python<'_>,
+    ) -> anyhow::Result<Option<ConfigurationWatcher>> {
+        self.configuration_file
+            .as_ref()
+            .map(|path| {
+                ConfigurationWatcher::new(
+                    py,
+                    path.clone(),
+                    Duration::from_millis(
+                        self.configuration_poll_interval_ms
+                            .unwrap_or(DEFAULT_CONFIGURATION_POLL_INTERVAL_MS),
+                    ),
+                )
+            })
+            .transpose()
+    }
+}
+
+/// Watches a file for updates of the configuration of a node.
+///
+/// The file is considered updated when its modification time changes. As the initial configuration is the one of the
+/// descriptor, the content of the file when the watcher is created is not considered an update.
+pub struct ConfigurationWatcher {
+    path: PathBuf,
+    poll_interval: Duration,
+    modified: Option<SystemTime>,
+}
+
+impl ConfigurationWatcher {
+    /// Creates a watcher of the JSON or YAML file at `path`, to be polled every `poll_interval`.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if the extension of the file is neither `.json`, `.yaml` nor `.yml`, or if the file is a
+    /// YAML file and the `yaml` module (of the PyYAML package) cannot be imported.
+    pub fn new(py: Python<'_>, path: PathBuf, poll_interval: Duration) -> anyhow::Result<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => (),
+            // NOTE: There is no YAML parser among our dependencies, we rely on the one of the Python environment.
+            Some("yaml") | Some("yml") => {
+                py.import("yaml").map_err(|e| {
+                    anyhow!(
+                        "Reading the configuration file < {} > requires the PyYAML package, install it with \
+                        `pip install zenoh-flow-python[yaml]`: {e}",
+                        path.display()
+                    )
+                })?;
+            }
+            _ => bail!(
+                "Unsupported configuration file < {} >: expected a `.json`, `.yaml` or `.yml` extension",
+                path.display()
+            ),
+        }
+
+        let modified = Self::modified(&path);
+        Ok(Self {
+            path,
+            poll_interval,
+            modified,
+        })
+    }
+
+    /// Returns the interval at which the file should be polled.
+    pub fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+
+    fn modified(path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    /// Returns the new configuration if the file changed since the last poll.
+    ///
+    /// A missing file is not an error: it is waited for.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if the file could not be read or parsed.
+    pub fn poll(&mut self, py: Python<'_>) -> anyhow::Result<Option<zf::Configuration>> {
+        let modified = Self::modified(&self.path);
+        if modified.is_none() || modified == self.modified {
+            return Ok(None);
+        }
+        self.modified = modified;
+
+        let content = std::fs::read_to_string(&self.path)
+            .map_err(|e| anyhow!("Failed to read < {} >: {e:?}", self.path.display()))?;
+
+        let mut configuration = match self.path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => serde_json::from_str::<serde_json::Value>(&content)
+                .map(zf::Configuration::from)
+                .map_err(|e| anyhow!("Failed to parse < {} >: {e:?}", self.path.display()))?,
+            Some("yaml") | Some("yml") => py
+                .import("yaml")
+                .and_then(|yaml| yaml.call_method1("safe_load", (content,)))
+                .and_then(py_into_configuration)
+                .map_err(|e| anyhow!("Failed to parse < {} >: {e}", self.path.display()))?,
+            _ => bail!(
+                "Unsupported configuration file < {} >: expected a `.json`, `.yaml` or `.yml` extension",
+                self.path.display()
+            ),
+        };
+
+        // NOTE: The section reserved to the wrappers cannot be updated, it is removed if present.
+        WrapperConfiguration::extract(&mut configuration)?;
+
+        Ok(Some(configuration))
+    }
+}
+
+/// Checks that the `zenoh_flow_python` module imported by the interpreter was built from the same version as the
+/// wrapper: their classes are otherwise not guaranteed to be compatible.
+///
+/// # Errors
+///
+/// This method will fail if the module could not be imported or if the versions differ.
+fn check_version(py: Python<'_>) -> anyhow::Result<()> {
+    let version = py
+        .import("zenoh_flow_python")
+        .and_then(|module| module.getattr("__version__"))
+        .and_then(|version| version.extract::<String>())
+        .map_err(|e| anyhow!("Failed to import the `zenoh_flow_python` module: {e:?}"))?;
+
+    if version != env!("CARGO_PKG_VERSION") {
+        bail!(
+            "The `zenoh_flow_python` module imported ({version}) does not match the version of the wrapper ({}), \
+            check the `venv` and `sys_path` of the node",
+            env!("CARGO_PKG_VERSION")
+        );
+    }
+
+    Ok(())
+}
+
+/// The ports of a node, which also determine its kind.
+pub enum NodePorts {
+    Source {
+        outputs: zf::Outputs,
+    },
+    Operator {
+        inputs: zf::Inputs,
+        outputs: zf::Outputs,
+    },
+    Sink {
+        inputs: zf::Inputs,
+    },
+}
+
+impl NodePorts {
+    fn kind(&self) -> &'static str {
+        match self {
+            NodePorts::Source { .. } => "Source",
+            NodePorts::Operator { .. } => "Operator",
+            NodePorts::Sink { .. } => "Sink",
+        }
+    }
+}
+
+/// A Python node and the asyncio event loop, dedicated to it, on which its coroutines run.
+///
+/// The event loop runs continuously on its own thread, from the creation of the node until it is dropped: the tasks
+/// the node creates (e.g. with `asyncio.create_task`) make progress between iterations.
+pub struct PythonState {
+    pub node_instance: Arc<PyObject>,
+    pub event_loop: Arc<PyObject>,
+    pub event_loop_thread: Option<JoinHandle<()>>,
+}
+
+impl PythonState {
+    /// Loads the Python node implemented at the library path of the `context`.
+    ///
+    /// The library path points to a Python file, the runtime selecting the wrapper from its extension. The node can
+    /// instead be loaded from the `module`, the `archive` or the `entry_point` of its [WrapperConfiguration], see the
+    /// `zenoh_flow_python.loader` module. The class of the node is then instantiated with the context,
+    /// the configuration (see [typed_configuration_into_py]) and the ports of the node.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if the node could not be loaded, if the configuration is invalid or if the class could
+    /// not be instantiated.
+    pub fn load(
+        context: zf::Context,
+        mut configuration: zf::Configuration,
+        ports: NodePorts,
+    ) -> zf::Result<Self> {
+        Python::with_gil(|py| {
+            let wrapper_configuration = WrapperConfiguration::extract(&mut configuration)?;
+            wrapper_configuration.prepare_environment(py)?;
+            check_version(py)?;
+            let configuration_watcher = wrapper_configuration.configuration_watcher(py)?;
+
+            // NOTE: The event loop is set for the current thread so that the constructor of the node can access it.
+            // It is only started, on its own thread, once the node is created.
+            let asyncio = py.import("asyncio")?;
+            let event_loop = asyncio.call_method0("new_event_loop")?;
+            asyncio.call_method1("set_event_loop", (event_loop,))?;
+
+            let kind = ports.kind();
+
+            let node_class = py
+                .import("zenoh_flow_python.loader")
+                .and_then(|loader| {
+                    let kwargs = [
+                        (
+                            "module",
+                            wrapper_configuration.module.as_deref().into_py(py),
+                        ),
+                        (
+                            "archive",
+                            wrapper_configuration.archive.as_deref().into_py(py),
+                        ),
+                        (
+                            "entry_point",
+                            wrapper_configuration.entry_point.as_deref().into_py(py),
+                        ),
+                        (
+                            "register",
+                            wrapper_configuration.register.as_deref().into_py(py),
+                        ),
+                    ]
+                    .into_py_dict(py);
+                    loader.call_method(
+                        "load",
+                        (
+                            context.library_path().to_string_lossy(),
+                            context.node_id().to_string(),
+                            kind,
+                        ),
+                        Some(kwargs),
+                    )
+                })
+                .map_err(|e| {
+                    anyhow!(
+                        "Failed to load the node from < {} >: {e:?}",
+                        context.library_path().display()
+                    )
+                })?;
+
+            let py_configuration = typed_configuration_into_py(node_class, configuration)?;
+
+            let node_id = context.node_id().to_string();
+            let context = Context::from(context).into_py(py);
+            let args = match ports {
+                NodePorts::Source { outputs } => PyTuple::new(
+                    py,
+                    [
+                        context,
+                        py_configuration,
+                        Outputs::from(outputs).into_py(py),
+                    ],
+                ),
+                NodePorts::Operator { inputs, outputs } => PyTuple::new(
+                    py,
+                    [
+                        context,
+                        py_configuration,
+                        Inputs::from(inputs).into_py(py),
+                        Outputs::from(outputs).into_py(py),
+                    ],
+                ),
+                NodePorts::Sink { inputs } => PyTuple::new(
+                    py,
+                    [context, py_configuration, Inputs::from(inputs).into_py(py)],
+                ),
+            };
+
+            // NOTE: `call1` will call the object pointed at by `node_class` with the provided parameters. This
+            // translates to creating a new instance of the class.
+            let node_instance = node_class
+                .call1(args)
+                .map_err(|e| anyhow!("Failed to create a {kind} instance: {e:?}"))?;
+
+            let event_loop: PyObject = event_loop.into();
+            let thread_event_loop = event_loop.clone_ref(py);
+            let event_loop_thread = std::thread::Builder::new()
+                .name(format!("zf-python-{node_id}"))
+                .spawn(move || {
+                    Python::with_gil(|py| {
+                        if let Err(e) = run_event_loop(thread_event_loop.as_ref(py)) {
+                            tracing::error!("The event loop of the node failed: {e:?}");
+                        }
+                    })
+                })
+                .map_err(|e| anyhow!("Failed to spawn the thread of the event loop: {e:?}"))?;
+
+            let node_instance: Arc<PyObject> = Arc::new(node_instance.into());
+            let event_loop = Arc::new(event_loop);
+            if let Some(watcher) = configuration_watcher {
+                ConfigurationPoller::start(py, node_instance.clone(), event_loop.clone(), watcher)
+                    .map_err(|e| {
+                        anyhow!("Failed to start watching the configuration file: {e:?}")
+                    })?;
+            }
+
+            Ok(PythonState {
+                node_instance,
+                event_loop,
+                event_loop_thread: Some(event_loop_thread),
+            })
+        })
+    }
+
+    /// Runs one iteration of the node: the coroutine returned by its `iteration` method is submitted to the event loop
+    /// of the node and waited for.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if the iteration failed or if the event loop of the node is no longer running.
+    pub fn iteration(&self) -> zf::Result<()> {
+        tracing::debug!("iteration");
+
+        Python::with_gil(|py| {
+            let node_instance = self
+                .node_instance
+                .downcast::<PyAny>(py)
+                .map_err(|e| anyhow!("Failed to downcast node instance to `PyAny`: {e:?}"))?;
+
+            let iteration_coroutine = node_instance
+                .call_method0("iteration")
+                .map_err(|e| anyhow!("Call to `iteration` failed with: {e:?}"))?;
+
+            self.run_coroutine(py, iteration_coroutine)
+                .map_err(|e| anyhow!("Iteration failed with: {e:?}"))?;
+
+            Ok(())
+        })
+    }
+
+    /// Submits the `coroutine` to the event loop of the node and blocks until it completes.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if the event loop is no longer running or if the coroutine raised an exception.
+    fn run_coroutine<'py>(
+        &self,
+        py: Python<'py>,
+        coroutine: &'py PyAny,
+    ) -> anyhow::Result<&'py PyAny> {
+        let running = matches!(&self.event_loop_thread, Some(thread) if !thread.is_finished());
+        if !running {
+            bail!("The event loop of the node is no longer running");
+        }
+
+        let future = py.import("asyncio")?.call_method1(
+            "run_coroutine_threadsafe",
+            (coroutine, PyObject::as_ref(&self.event_loop, py)),
+        )?;
+
+        // NOTE: `result` releases the GIL while it waits, letting the thread of the event loop run the coroutine.
+        Ok(future.call_method0("result")?)
+    }
+}
+
+impl Drop for PythonState {
+    fn drop(&mut self) {
+        Python::with_gil(|py| {
+            self.node_instance
+                .call_method0(py, "finalize")
+                .expect("Failed to call `finalize` on the internal Python state");
+
+            let Some(event_loop_thread) = self.event_loop_thread.take() else {
+                return;
+            };
+
+            let event_loop = PyObject::as_ref(&self.event_loop, py);
+            if let Err(e) = event_loop
+                .getattr("stop")
+                .and_then(|stop| event_loop.call_method1("call_soon_threadsafe", (stop,)))
+            {
+                tracing::error!("Failed to stop the event loop of the node: {e:?}");
+                return;
+            }
+
+            // NOTE: The GIL must be released for the thread of the event loop to terminate.
+            if py.allow_threads(|| event_loop_thread.join()).is_err() {
+                tracing::error!("The thread of the event loop of the node panicked");
+            }
+        });
+    }
+}
+
+/// Polls the configuration file of a node, on the event loop of the node, and passes the updates to its
+/// `on_configuration_update` method.
+///
+/// The poller schedules itself on the event loop at every poll interval: the node receives the updates even while its
+/// iteration waits, e.g. on one of its inputs. If `on_configuration_update` is a coroutine, it runs as a task of the
+/// event loop and the file is polled again once it completes.
+///
+/// An invalid update, or a failed call to `on_configuration_update`, is logged and ignored: the node keeps running with
+/// its current configuration.
+#[pyclass]
+pub struct ConfigurationPoller {
+    node_instance: Arc<PyObject>,
+    event_loop: Arc<PyObject>,
+    watcher: ConfigurationWatcher,
+}
+
+impl ConfigurationPoller {
+    /// Schedules the first poll of the `watcher` on the `event_loop`.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if the poll could not be scheduled.
+    pub fn start(
+        py: Python<'_>,
+        node_instance: Arc<PyObject>,
+        event_loop: Arc<PyObject>,
+        watcher: ConfigurationWatcher,
+    ) -> PyResult<()> {
+        let poller = Py::new(
+            py,
+            Self {
+                node_instance,
+                event_loop: event_loop.clone(),
+                watcher,
+            },
+        )?;
+
+        // NOTE: The event loop runs on another thread, only `call_soon_threadsafe` can be called from this one.
+        event_loop.call_method1(py, "call_soon_threadsafe", (poller,))?;
+        Ok(())
+    }
+
+    /// Polls the watcher and calls `on_configuration_update` if the configuration was updated, returning the coroutine
+    /// it returned, if any.
+    ///
+    /// # Errors
+    ///
+    /// This method will fail if the call to `on_configuration_update` failed.
+    fn update<'py>(&mut self, py: Python<'py>) -> anyhow::Result<Option<&'py PyAny>> {
+        let configuration = match self.watcher.poll(py) {
+            Ok(Some(configuration)) => configuration,
+            Ok(None) => return Ok(None),
+            Err(e) => {
+                tracing::error!("Ignoring configuration update: {e:?}");
+                return Ok(None);
+            }
+        };
+
+        let node_instance = self.node_instance.clone_ref(py).into_ref(py);
+        if !node_instance.hasattr("on_configuration_update")? {
+            tracing::warn!(
+                "Ignoring configuration update: the node has no `on_configuration_update` method"
+            );
+            return Ok(None);
+        }
+
+        let py_configuration =
+            match typed_configuration_into_py(node_instance.get_type(), configuration) {
+                Ok(py_configuration) => py_configuration,
+                Err(e) => {
+                    tracing::error!("Ignoring configuration update: {e:?}");
+                    return Ok(None);
+                }
+            };
+
+        let update = node_instance.call_method1("on_configuration_update", (py_configuration,))?;
+        if py
+            .import("asyncio")?
+            .call_method1("iscoroutine", (update,))?
+            .is_true()?
+        {
+            return Ok(Some(update));
+        }
+
+        Ok(None)
+    }
+}
+
+#[pymethods]
+impl ConfigurationPoller {
+    /// Polls the configuration file and schedules the next poll.
+    ///
+    /// The poller is called without argument by the event loop, or with the task running `on_configuration_update`
+    /// once it completes.
+    #[pyo3(signature = (*args))]
+    fn __call__(mut slf: PyRefMut<'_, Self>, args: &PyTuple) -> PyResult<()> {
+        let py = slf.py();
+
+        if let Ok(task) = args.get_item(0) {
+            // NOTE: The task is cancelled when the node is dropped, the event loop is then stopping.
+            if task.call_method0("cancelled")?.is_true()? {
+                return Ok(());
+            }
+            let exception = task.call_method0("exception")?;
+            if !exception.is_none() {
+                tracing::error!("Call to `on_configuration_update` failed with: {exception:?}");
+            }
+        }
+
+        let update = match slf.update(py) {
+            Ok(update) => update,
+            Err(e) => {
+                tracing::error!("Call to `on_configuration_update` failed with: {e:?}");
+                None
+            }
+        };
+
+        let event_loop = slf.event_loop.clone_ref(py).into_ref(py);
+        let poll_interval = slf.watcher.poll_interval().as_secs_f64();
+        let poller: PyObject = slf.into_py(py);
+        match update {
+            Some(coroutine) => {
+                let task = event_loop.call_method1("create_task", (coroutine,))?;
+                task.call_method1("add_done_callback", (poller,))?;
+            }
+            None => {
+                event_loop.call_method1("call_later", (poll_interval, poller))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs the `event_loop` until it is stopped, then cancels the tasks that are still pending and closes it.
+///
+/// # Errors
+///
+/// This function will fail if the event loop could not be run or closed.
+fn run_event_loop(event_loop: &PyAny) -> PyResult<()> {
+    let py = event_loop.py();
+    let asyncio = py.import("asyncio")?;
+    asyncio.call_method1("set_event_loop", (event_loop,))?;
+
+    let result = event_loop.call_method0("run_forever");
+
+    let tasks = asyncio.call_method1("all_tasks", (event_loop,))?;
+    for task in tasks.iter()? {
+        task?.call_method0("cancel")?;
+    }
+    let tasks = PyTuple::new(py, tasks.iter()?.collect::<PyResult<Vec<_>>>()?);
+    let pending = asyncio
+        .getattr("gather")?
+        .call(tasks, Some([("return_exceptions", true)].into_py_dict(py)))?;
+    event_loop.call_method1("run_until_complete", (pending,))?;
+    event_loop.call_method1(
+        "run_until_complete",
+        (event_loop.call_method0("shutdown_asyncgens")?,),
+    )?;
+    event_loop.call_method0("close")?;
+
+    result.map(|_| ())
+}
@@ -0,0 +1,239 @@
+#
+# Copyright © 2022 ZettaScale Technology
+#
+# This program and the accompanying materials are made available under the
+# terms of the Eclipse Public License 2.0 which is available at
+# http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+# which is available at https://www.apache.org/licenses/LICENSE-2.0.
+#
+# SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+#
+# Contributors:
+#   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+#
+
+import dataclasses
+import os
+import unittest
+from typing import Any, Dict, List, Optional, Tuple, TypedDict
+from unittest import mock
+
+from zenoh_flow_python.configuration import (
+    ConfigurationError,
+    build,
+    interpolate,
+    validate,
+)
+
+try:
+    import pydantic
+except ImportError:
+    pydantic = None
+
+try:
+    import jsonschema
+except ImportError:
+    jsonschema = None
+
+
+@dataclasses.dataclass
+class Sensor:
+    name: str
+    threshold: float = 0.5
+
+
+@dataclasses.dataclass
+class DataclassSchema:
+    port: int
+    sensors: List[Sensor]
+    enabled: bool = True
+    label: Optional[str] = None
+
+
+class TypedDictSchema(TypedDict):
+    port: int
+    ratio: float
+
+
+JSON_SCHEMA: Dict[str, Any] = {
+    "type": "object",
+    "properties": {
+        "port": {"type": "integer"},
+        "ratio": {"type": "number", "default": 0.5},
+        "tags": {"type": "array", "items": {"type": "string"}},
+    },
+    "required": ["port"],
+    "additionalProperties": False,
+}
+
+
+class ConfigurationTestCase(unittest.TestCase):
+    def assertErrors(
+        self, schema: Any, configuration: Any, errors: List[Tuple[str, str]]
+    ) -> None:
+        with self.assertRaises(ConfigurationError) as raised:
+            build(schema, configuration)
+        self.assertEqual(raised.exception.errors, errors)
+        self.assertEqual(
+            str(raised.exception),
+            "\n".join(f"{path}: {message}" for path, message in errors),
+        )
+
+
+class TestInterpolation(ConfigurationTestCase):
+    @mock.patch.dict(os.environ, {"HOST": "localhost"})
+    def test_variables(self):
+        self.assertEqual(
+            interpolate(
+                {
+                    "url": "http://${HOST}:${PORT:-8080}",
+                    "price": "$$5",
+                    "hosts": ["${HOST}", 1],
+                }
+            ),
+            {
+                "url": "http://localhost:8080",
+                "price": "$5",
+                "hosts": ["localhost", 1],
+            },
+        )
+
+    @mock.patch.dict(os.environ, {"PORT": "8080"}, clear=True)
+    def test_without_schema(self):
+        configuration = {"port": "${PORT}", "host": "${HOST}", "price": "$$5"}
+        self.assertEqual(build(None, configuration), configuration)
+
+    @mock.patch.dict(os.environ, {}, clear=True)
+    def test_unset_variable(self):
+        self.assertErrors(
+            TypedDictSchema,
+            {"port": "${PORT}", "ratio": 0.5},
+            [("configuration.port", "environment variable 'PORT' is not set")],
+        )
+
+
+class TestDataclass(ConfigurationTestCase):
+    def test_build(self):
+        configuration = build(
+            DataclassSchema,
+            {"port": 8080, "sensors": [{"name": "a"}, {"name": "b", "threshold": 1}]},
+        )
+        self.assertEqual(
+            configuration,
+            DataclassSchema(
+                port=8080,
+                sensors=[Sensor("a"), Sensor("b", 1)],
+                enabled=True,
+                label=None,
+            ),
+        )
+
+    @mock.patch.dict(
+        os.environ, {"PORT": "8080", "ENABLED": "off", "THRESHOLD": "0.7"}
+    )
+    def test_interpolated_values_are_converted(self):
+        configuration = build(
+            DataclassSchema,
+            {
+                "port": "${PORT}",
+                "enabled": "${ENABLED}",
+                "sensors": [{"name": "${PORT}", "threshold": "${THRESHOLD}"}],
+            },
+        )
+        self.assertEqual(configuration.port, 8080)
+        self.assertIs(configuration.enabled, False)
+        self.assertEqual(configuration.sensors, [Sensor("8080", 0.7)])
+
+    def test_literal_strings_are_not_converted(self):
+        self.assertErrors(
+            DataclassSchema,
+            {"port": "8080", "sensors": []},
+            [("configuration.port", "expected int, got str")],
+        )
+
+    def test_errors(self):
+        self.assertErrors(
+            DataclassSchema,
+            {"prot": 8080, "sensors": [{"name": "a", "threshold": "high"}]},
+            [
+                ("configuration.prot", "unknown field, did you mean 'port'?"),
+                ("configuration.port", "missing required field"),
+                ("configuration.sensors[0].threshold", "expected float, got str"),
+            ],
+        )
+
+    def test_absent_configuration(self):
+        with self.assertRaises(ConfigurationError) as raised:
+            validate(DataclassSchema, None)
+        self.assertEqual(
+            raised.exception.errors,
+            [
+                ("configuration.port", "missing required field"),
+                ("configuration.sensors", "missing required field"),
+            ],
+        )
+
+
+class TestTypedDict(ConfigurationTestCase):
+    @mock.patch.dict(os.environ, {"RATIO": "0.25"})
+    def test_build(self):
+        self.assertEqual(
+            build(TypedDictSchema, {"port": 8080, "ratio": "${RATIO}"}),
+            {"port": 8080, "ratio": 0.25},
+        )
+
+    def test_errors(self):
+        self.assertErrors(
+            TypedDictSchema,
+            {"port": True},
+            [
+                ("configuration.port", "expected int, got bool"),
+                ("configuration.ratio", "missing required field"),
+            ],
+        )
+
+
+@unittest.skipIf(pydantic is None, "requires pydantic")
+class TestPydantic(ConfigurationTestCase):
+    def setUp(self):
+        class PydanticSchema(pydantic.BaseModel):
+            port: int
+            ratio: float = 0.5
+
+        self.schema = PydanticSchema
+
+    @mock.patch.dict(os.environ, {"PORT": "8080"})
+    def test_build(self):
+        configuration = build(self.schema, {"port": "${PORT}"})
+        self.assertIsInstance(configuration, self.schema)
+        self.assertEqual((configuration.port, configuration.ratio), (8080, 0.5))
+
+    def test_errors(self):
+        with self.assertRaises(ConfigurationError) as raised:
+            build(self.schema, {"ratio": "high"})
+        self.assertEqual(
+            [path for path, _ in raised.exception.errors],
+            ["configuration.port", "configuration.ratio"],
+        )
+
+
+@unittest.skipIf(jsonschema is None, "requires jsonschema")
+class TestJsonSchema(ConfigurationTestCase):
+    @mock.patch.dict(os.environ, {"PORT": "8080", "TAG": "1"})
+    def test_build(self):
+        self.assertEqual(
+            build(JSON_SCHEMA, {"port": "${PORT}", "tags": ["${TAG}"]}),
+            {"port": 8080, "ratio": 0.5, "tags": ["1"]},
+        )
+
+    def test_errors(self):
+        with self.assertRaises(ConfigurationError) as raised:
+            build(JSON_SCHEMA, {"port": 8080, "tags": [1]})
+        self.assertEqual(
+            raised.exception.errors,
+            [("configuration.tags[0]", "1 is not of type 'string'")],
+        )
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,326 @@
+#
+# Copyright © 2022 ZettaScale Technology
+#
+# This program and the accompanying materials are made available under the
+# terms of the Eclipse Public License 2.0 which is available at
+# http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+# which is available at https://www.apache.org/licenses/LICENSE-2.0.
+#
+# SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+#
+# Contributors:
+#   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+#
+
+import os
+import sys
+import tempfile
+import textwrap
+import unittest
+import zipfile
+from typing import Dict
+
+from zenoh_flow_python.loader import ENTRY_POINT_GROUP, load
+
+
+class LoaderTestCase(unittest.TestCase):
+    """Writes the files of the nodes in a temporary folder on the `sys.path`."""
+
+    def setUp(self):
+        self.directory = tempfile.TemporaryDirectory()
+        self.root = self.directory.name
+        sys.path.insert(0, self.root)
+        self.modules = set(sys.modules)
+
+    def tearDown(self):
+        sys.path.remove(self.root)
+        for name in set(sys.modules) - self.modules:
+            del sys.modules[name]
+        self.directory.cleanup()
+
+    def write(self, files: Dict[str, str]) -> None:
+        for name, source in files.items():
+            path = os.path.join(self.root, name)
+            os.makedirs(os.path.dirname(path), exist_ok=True)
+            with open(path, "w") as file:
+                file.write(textwrap.dedent(source))
+
+    def path(self, name: str) -> str:
+        return os.path.join(self.root, name)
+
+
+class TestLibrary(LoaderTestCase):
+    def test_file(self):
+        self.write(
+            {
+                "node.py": """
+                class MyOperator:
+                    pass
+
+                def register():
+                    return MyOperator
+                """
+            }
+        )
+        node_class = load(self.path("node.py"), "my-operator", "Operator")
+        self.assertEqual(node_class.__name__, "MyOperator")
+
+    def test_nodes_do_not_collide(self):
+        self.write({"node.py": "class MySink: pass\nregister = MySink\n"})
+        first = load(self.path("node.py"), "sink", "Sink")
+        second = load(self.path("node.py"), "sink", "Sink")
+        self.assertIsNot(first, second)
+        self.assertNotEqual(first.__module__, second.__module__)
+
+    def test_file_in_package(self):
+        self.write(
+            {
+                "package/__init__.py": "",
+                "package/common.py": "THRESHOLD = 0.5\n",
+                "package/node.py": """
+                from .common import THRESHOLD
+
+                class MySource:
+                    threshold = THRESHOLD
+
+                register = MySource
+                """,
+            }
+        )
+        node_class = load(self.path("package/node.py"), "my-source", "Source")
+        self.assertEqual(node_class.threshold, 0.5)
+
+    def test_package(self):
+        self.write(
+            {
+                "package/__init__.py": "from .node import register\n",
+                "package/node.py": """
+                class MySink:
+                    pass
+
+                def register():
+                    return MySink
+                """,
+            }
+        )
+        node_class = load(self.path("package/__init__.py"), "my-sink", "Sink")
+        self.assertEqual(node_class.__name__, "MySink")
+
+    def test_not_a_file(self):
+        with self.assertRaisesRegex(ImportError, "is not a file"):
+            load(self.root, "my-sink", "Sink")
+
+    def test_no_register(self):
+        self.write({"node.py": "class MySink: pass\n"})
+        with self.assertRaisesRegex(
+            ImportError, "does not expose a `register` function"
+        ):
+            load(self.path("node.py"), "my-sink", "Sink")
+
+
+class TestModule(LoaderTestCase):
+    def setUp(self):
+        super().setUp()
+        self.write(
+            {
+                "library.py": "",
+                "my_package/__init__.py": "",
+                "my_package/nodes.py": """
+                class MyOperator:
+                    pass
+
+                register = MyOperator
+                """,
+            }
+        )
+
+    def test_module(self):
+        node_class = load(
+            self.path("library.py"), "op", "Operator", module="my_package.nodes"
+        )
+        self.assertEqual(node_class.__qualname__, "MyOperator")
+
+    def test_spec(self):
+        node_class = load(
+            self.path("library.py"),
+            "op",
+            "Operator",
+            module="my_package.nodes:MyOperator",
+        )
+        self.assertEqual(node_class.__module__, "my_package.nodes")
+
+    def test_errors(self):
+        with self.assertRaisesRegex(ImportError, "is not a valid `module`"):
+            load(self.path("library.py"), "op", "Operator", module="my package")
+        with self.assertRaisesRegex(ImportError, "has no attribute `Missing`"):
+            load(
+                self.path("library.py"),
+                "op",
+                "Operator",
+                module="my_package.nodes:Missing",
+            )
+
+    def test_exclusive(self):
+        with self.assertRaisesRegex(
+            ImportError, "found: `module`, `archive`"
+        ):
+            load(
+                self.path("library.py"),
+                "op",
+                "Operator",
+                module="my_package.nodes",
+                archive=self.path("node.zip"),
+            )
+
+
+class TestArchive(LoaderTestCase):
+    def test_archive(self):
+        self.write({"library.py": ""})
+        with zipfile.ZipFile(self.path("node.zip"), "w") as archive:
+            archive.writestr("__init__.py", "from .node import MySink as register\n")
+            archive.writestr("node.py", "class MySink: pass\n")
+
+        node_class = load(
+            self.path("library.py"), "sink", "Sink", archive=self.path("node.zip")
+        )
+        self.assertEqual(node_class.__name__, "MySink")
+
+    def test_not_a_package(self):
+        self.write({"library.py": ""})
+        with zipfile.ZipFile(self.path("node.zip"), "w") as archive:
+            archive.writestr("node.py", "class MySink: pass\n")
+
+        with self.assertRaisesRegex(ImportError, "has no `__init__.py` at its root"):
+            load(
+                self.path("library.py"), "sink", "Sink", archive=self.path("node.zip")
+            )
+
+
+class TestEntryPoint(LoaderTestCase):
+    def setUp(self):
+        super().setUp()
+        self.write(
+            {
+                "library.py": "",
+                "my_nodes.py": "class MySource: pass\n",
+                "my_nodes-1.0.dist-info/METADATA": """
+                Metadata-Version: 2.1
+                Name: my-nodes
+                Version: 1.0
+                """,
+                "my_nodes-1.0.dist-info/entry_points.txt": f"""
+                [{ENTRY_POINT_GROUP}]
+                my-source = my_nodes:MySource
+                """,
+            }
+        )
+
+    def test_entry_point(self):
+        node_class = load(
+            self.path("library.py"), "source", "Source", entry_point="my-source"
+        )
+        self.assertEqual(node_class.__name__, "MySource")
+
+    def test_missing(self):
+        with self.assertRaisesRegex(ImportError, "No entry point `missing`"):
+            load(self.path("library.py"), "source", "Source", entry_point="missing")
+
+
+class TestLookup(LoaderTestCase):
+    def test_register_attribute(self):
+        self.write(
+            {
+                "node.py": """
+                class Left:
+                    pass
+
+                class Right:
+                    pass
+
+                def make_nodes():
+                    return {"left": Left, "right": Right}
+                """
+            }
+        )
+        node_class = load(self.path("node.py"), "right", "Sink", register="make_nodes")
+        self.assertEqual(node_class.__name__, "Right")
+
+        with self.assertRaisesRegex(
+            ImportError, "provides no class for the node `up`, it provides: left, right"
+        ):
+            load(self.path("node.py"), "up", "Sink", register="make_nodes")
+
+        with self.assertRaisesRegex(ImportError, "does not expose the attribute"):
+            load(self.path("node.py"), "left", "Sink", register="missing")
+
+    def test_decorators(self):
+        self.write(
+            {
+                "node.py": """
+                import zenoh_flow_python
+
+                @zenoh_flow_python.operator
+                class MyOperator:
+                    pass
+
+                @zenoh_flow_python.sink
+                class MySink:
+                    pass
+
+                @zenoh_flow_python.sink("logger")
+                class Logger:
+                    pass
+                """
+            }
+        )
+        self.assertEqual(
+            load(self.path("node.py"), "op", "Operator").__name__, "MyOperator"
+        )
+        self.assertEqual(load(self.path("node.py"), "sink", "Sink").__name__, "MySink")
+        self.assertEqual(
+            load(self.path("node.py"), "logger", "Sink").__name__, "Logger"
+        )
+        with self.assertRaisesRegex(ImportError, "registers no Source"):
+            load(self.path("node.py"), "source", "Source")
+
+    def test_ambiguous_decorators(self):
+        self.write(
+            {
+                "node.py": """
+                import zenoh_flow_python
+
+                @zenoh_flow_python.sink
+                class First:
+                    pass
+
+                @zenoh_flow_python.sink
+                class Second:
+                    pass
+                """
+            }
+        )
+        with self.assertRaisesRegex(
+            ImportError, "registers several Sink classes .*: First, Second"
+        ):
+            load(self.path("node.py"), "sink", "Sink")
+
+    def test_decorators_in_submodules(self):
+        self.write(
+            {
+                "package/__init__.py": "from .sinks import logger\n",
+                "package/sinks/__init__.py": "",
+                "package/sinks/logger.py": """
+                import zenoh_flow_python
+
+                @zenoh_flow_python.sink
+                class Logger:
+                    pass
+                """,
+            }
+        )
+        node_class = load(self.path("package/__init__.py"), "logger", "Sink")
+        self.assertEqual(node_class.__name__, "Logger")
+
+
+if __name__ == "__main__":
+    unittest.main()
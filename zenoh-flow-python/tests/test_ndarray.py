@@ -0,0 +1,71 @@
+#
+# Copyright © 2022 ZettaScale Technology
+#
+# This program and the accompanying materials are made available under the
+# terms of the Eclipse Public License 2.0 which is available at
+# http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+# which is available at https://www.apache.org/licenses/LICENSE-2.0.
+#
+# SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+#
+# Contributors:
+#   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+#
+
+import unittest
+
+from zenoh_flow_python import NDArrayCodec, SerializationError
+
+try:
+    import numpy
+except ImportError:
+    numpy = None
+
+
+@unittest.skipIf(numpy is None, "requires numpy")
+class TestNDArrayCodec(unittest.TestCase):
+    def setUp(self):
+        self.codec = NDArrayCodec()
+
+    def assertRoundTrip(self, array) -> None:
+        payload = self.codec.encode(array)
+        for buffer in (payload, memoryview(payload)):
+            decoded = self.codec.decode(buffer)
+            self.assertEqual(decoded.dtype, array.dtype)
+            self.assertEqual(decoded.shape, array.shape)
+            numpy.testing.assert_array_equal(decoded, array)
+
+    def test_contiguous(self):
+        array = numpy.arange(12, dtype="<f8").reshape(3, 4)
+        self.assertRoundTrip(array)
+        self.assertRoundTrip(numpy.asfortranarray(array))
+        self.assertRoundTrip(array.astype(">u2"))
+        self.assertRoundTrip(numpy.array(1.5, dtype="<f4"))
+
+    def test_strided_views(self):
+        array = numpy.arange(24, dtype="<i4").reshape(4, 6)
+        self.assertRoundTrip(array[::2])
+        self.assertRoundTrip(array[:, ::3])
+        self.assertRoundTrip(array[::-1, 1:5].T)
+
+    def test_empty(self):
+        for shape in [(0,), (0, 3), (2, 0, 4)]:
+            self.assertRoundTrip(numpy.empty(shape, dtype="<f8"))
+
+    def test_datetimes(self):
+        self.assertRoundTrip(
+            numpy.array(["2024-01-01T00:00", "2024-06-01T12:30"], dtype="<M8[ns]")
+        )
+        self.assertRoundTrip(numpy.arange(6, dtype="<m8[s]").reshape(2, 3)[:, ::2])
+
+    def test_unsupported_dtypes(self):
+        for array in [
+            numpy.array([object(), None]),
+            numpy.zeros(2, dtype=[("x", "<f8"), ("y", "<i4")]),
+        ]:
+            with self.assertRaises(SerializationError):
+                self.codec.encode(array)
+
+
+if __name__ == "__main__":
+    unittest.main()
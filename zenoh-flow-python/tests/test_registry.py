@@ -0,0 +1,96 @@
+#
+# Copyright © 2022 ZettaScale Technology
+#
+# This program and the accompanying materials are made available under the
+# terms of the Eclipse Public License 2.0 which is available at
+# http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+# which is available at https://www.apache.org/licenses/LICENSE-2.0.
+#
+# SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+#
+# Contributors:
+#   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+#
+
+import sys
+import types
+import unittest
+
+from zenoh_flow_python.registry import (
+    RegisteredNode,
+    operator,
+    registered_nodes,
+    sink,
+    source,
+)
+
+
+class TestRegistry(unittest.TestCase):
+    def setUp(self):
+        self.modules = set(sys.modules)
+
+    def tearDown(self):
+        for name in set(sys.modules) - self.modules:
+            del sys.modules[name]
+
+    def module(self, name: str, source: str) -> types.ModuleType:
+        module = types.ModuleType(name)
+        if "." not in name:
+            module.__path__ = []
+        sys.modules[name] = module
+        exec(source, module.__dict__)
+        return module
+
+    def test_decorators(self):
+        module = self.module(
+            "_registry_nodes",
+            "from zenoh_flow_python.registry import operator, sink\n"
+            "@operator\n"
+            "class MyOperator: pass\n"
+            "@sink('logger')\n"
+            "class Logger: pass\n",
+        )
+        self.assertEqual(
+            registered_nodes(module),
+            {
+                "MyOperator": RegisteredNode("Operator", None, module.MyOperator),
+                "Logger": RegisteredNode("Sink", "logger", module.Logger),
+            },
+        )
+
+    def test_decorator_returns_the_class(self):
+        class MySource:
+            pass
+
+        self.assertIs(source(MySource), MySource)
+        self.assertIs(source("my-source")(MySource), MySource)
+
+    def test_not_a_class(self):
+        with self.assertRaisesRegex(TypeError, "@operator can only decorate a class"):
+            operator(lambda: None)
+        with self.assertRaisesRegex(TypeError, "@sink can only decorate a class"):
+            sink("logger")(lambda: None)
+
+    def test_submodules(self):
+        package = self.module("_registry_package", "")
+        submodule = self.module(
+            "_registry_package.sinks",
+            "from zenoh_flow_python.registry import sink\n"
+            "@sink\n"
+            "class Logger: pass\n",
+        )
+        self.assertEqual(
+            registered_nodes(package),
+            {"sinks.Logger": RegisteredNode("Sink", None, submodule.Logger)},
+        )
+        self.assertEqual(
+            registered_nodes(submodule),
+            {"Logger": RegisteredNode("Sink", None, submodule.Logger)},
+        )
+
+    def test_no_registration(self):
+        self.assertEqual(registered_nodes(self.module("_registry_empty", "")), {})
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -13,6 +13,8 @@
 #
 
 from .zenoh_flow_python import *
+from .zenoh_flow_python import __version__ as __version__
 
 from . import nodes as nodes
 from .nodes import Sink
+from .registry import operator, sink, source
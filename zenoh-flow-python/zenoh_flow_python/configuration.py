@@ -0,0 +1,640 @@
+#
+# Copyright © 2022 ZettaScale Technology
+#
+# This program and the accompanying materials are made available under the
+# terms of the Eclipse Public License 2.0 which is available at
+# http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+# which is available at https://www.apache.org/licenses/LICENSE-2.0.
+#
+# SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+#
+# Contributors:
+#   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+#
+
+"""
+Validation and construction of the configuration of a node from the schema it
+declares.
+
+A node declares the schema of its configuration through the
+`configuration_schema` class attribute, which can be:
+- a dataclass,
+- a `TypedDict`,
+- a pydantic model (v1 or v2),
+- a JSON Schema, as a dictionary (this requires the `jsonschema` package).
+
+If the node declares a schema, before creating the node, the Zenoh-Flow
+wrappers:
+1. Replace, in all the string values, the references to environment variables
+   `${NAME}` (or `${NAME:-default}`) by their value. `$$` produces a `$`.
+2. Convert the interpolated values to the type the schema declares for them:
+   `port: int` set to `"${PORT}"` receives an integer. Only integers, floats
+   and booleans are converted, and only if the value was interpolated.
+3. Validate the configuration, reporting every mismatch with its path, e.g.:
+
+       configuration.sensors[1].threshold: expected float, got str
+
+4. Build the object passed to the constructor of the node: an instance of the
+   dataclass or of the pydantic model, or a dictionary for a `TypedDict` or a
+   JSON Schema. The fields that are absent take their declared default.
+"""
+
+import copy
+import dataclasses
+import difflib
+import os
+import re
+import types
+import typing
+from typing import Any, Dict, List, Literal, Tuple, Union
+
+__all__ = ["ConfigurationError", "build", "interpolate", "validate"]
+
+_ROOT = "configuration"
+_ENVIRONMENT_VARIABLE = re.compile(
+    r"\$\$|\$\{([A-Za-z_][A-Za-z0-9_]*)(?::-([^}]*))?\}"
+)
+
+
+class ConfigurationError(ValueError):
+    """
+    Raised when a configuration does not match its schema.
+
+    `errors` lists every mismatch as a `(path, message)` tuple.
+    """
+
+    def __init__(self, errors: List[Tuple[str, str]]):
+        self.errors = errors
+        super().__init__(
+            "\n".join(f"{path}: {message}" for path, message in errors)
+        )
+
+
+def validate(schema: Any, configuration: Any) -> None:
+    """
+    Validates the configuration against the schema.
+
+    An absent configuration (i.e. None) is validated as an empty one, such
+    that missing required fields are reported.
+
+    # Exception
+
+    A `ConfigurationError` is raised if the configuration does not match the
+    schema.
+    """
+    if schema is None:
+        return
+    if configuration is None:
+        configuration = {}
+
+    errors: List[Tuple[str, str]] = []
+    if _is_pydantic_model(schema):
+        _validate_pydantic(schema, configuration, errors)
+    elif isinstance(schema, dict):
+        _validate_json_schema(schema, configuration, errors)
+    else:
+        _check(configuration, schema, _ROOT, errors)
+
+    if errors:
+        raise ConfigurationError(errors)
+
+
+def interpolate(configuration: Any) -> Any:
+    """
+    Returns a copy of the configuration where, in all the string values, the
+    references to environment variables `${NAME}` are replaced by their value.
+
+    If the variable is not set, the default provided with `${NAME:-default}`
+    is used. `$$` produces a single `$`.
+
+    # Exception
+
+    A `ConfigurationError` is raised if a variable that has no default is not
+    set.
+    """
+    return _plain(_interpolate_or_raise(configuration))
+
+
+def build(schema: Any, configuration: Any) -> Any:
+    """
+    Interpolates the environment variables, converts the interpolated values
+    to their declared type, validates and builds the object described by the
+    schema. Without schema, the configuration is returned untouched.
+
+    # Exception
+
+    A `ConfigurationError` is raised if the interpolation or the validation
+    failed.
+    """
+    if schema is None:
+        return configuration
+    if configuration is None:
+        configuration = {}
+
+    configuration = _interpolate_or_raise(configuration)
+    if _is_pydantic_model(schema):
+        # NOTE: pydantic converts strings to the declared scalar types itself.
+        configuration = _plain(configuration)
+    elif isinstance(schema, dict):
+        configuration = _coerce_json_schema(schema, configuration)
+    else:
+        configuration = _coerce(configuration, schema)
+    validate(schema, configuration)
+
+    if _is_pydantic_model(schema):
+        if hasattr(schema, "model_validate"):
+            return schema.model_validate(configuration)
+        return schema.parse_obj(configuration)
+    if isinstance(schema, dict):
+        return _apply_json_schema_defaults(schema, configuration)
+    return _build(configuration, schema)
+
+
+class _Interpolated(str):
+    """A string value in which at least one variable was interpolated."""
+
+
+def _interpolate_or_raise(configuration: Any) -> Any:
+    errors: List[Tuple[str, str]] = []
+    configuration = _interpolate(configuration, _ROOT, errors)
+    if errors:
+        raise ConfigurationError(errors)
+    return configuration
+
+
+def _interpolate(
+    value: Any, path: str, errors: List[Tuple[str, str]]
+) -> Any:
+    if isinstance(value, str):
+        interpolated = False
+
+        def replace(match: "re.Match[str]") -> str:
+            nonlocal interpolated
+            name, default = match.group(1), match.group(2)
+            if name is None:
+                return "$"
+            interpolated = True
+            if name in os.environ:
+                return os.environ[name]
+            if default is not None:
+                return default
+            errors.append(
+                (path, f"environment variable '{name}' is not set")
+            )
+            return match.group(0)
+
+        value = _ENVIRONMENT_VARIABLE.sub(replace, value)
+        return _Interpolated(value) if interpolated else value
+
+    if isinstance(value, list):
+        return [
+            _interpolate(item, _path(path, index), errors)
+            for index, item in enumerate(value)
+        ]
+
+    if isinstance(value, dict):
+        return {
+            key: _interpolate(item, _path(path, key), errors)
+            for key, item in value.items()
+        }
+
+    return value
+
+
+def _plain(value: Any) -> Any:
+    """Returns a copy of `value` where the interpolated strings are `str`."""
+    if isinstance(value, _Interpolated):
+        return str(value)
+    if isinstance(value, list):
+        return [_plain(item) for item in value]
+    if isinstance(value, dict):
+        return {key: _plain(item) for key, item in value.items()}
+    return value
+
+
+_TRUE = {"true", "yes", "on", "1"}
+_FALSE = {"false", "no", "off", "0"}
+
+
+def _convert(value: str, target: Any) -> Any:
+    """
+    Converts the interpolated `value` to the scalar type `target`, or returns
+    it unchanged if it cannot be, in which case the validation reports it.
+    """
+    text = value.strip()
+    try:
+        if target is bool:
+            if text.lower() in _TRUE:
+                return True
+            if text.lower() in _FALSE:
+                return False
+        elif target is int:
+            return int(text)
+        elif target is float:
+            return float(text)
+    except ValueError:
+        pass
+    return value
+
+
+def _coerce(value: Any, annotation: Any) -> Any:
+    """
+    Returns a copy of `value` where the interpolated strings are converted to
+    the scalar type declared for them by `annotation`.
+    """
+    if isinstance(value, _Interpolated):
+        if annotation in (bool, int, float):
+            return _plain(_convert(value, annotation))
+        origin = typing.get_origin(annotation)
+        if origin is Union or origin is getattr(types, "UnionType", None):
+            # NOTE: A string stays a string if the union accepts strings.
+            args = typing.get_args(annotation)
+            if str not in args:
+                for arg in args:
+                    converted = _convert(value, arg)
+                    if not isinstance(converted, str):
+                        return converted
+        return str(value)
+
+    if _is_optional(annotation):
+        return _coerce(value, _optional_type(annotation))
+
+    origin = typing.get_origin(annotation)
+    args = typing.get_args(annotation)
+
+    if isinstance(value, dict):
+        fields: Dict[Any, Any] = {}
+        if isinstance(annotation, type) and dataclasses.is_dataclass(annotation):
+            fields = _dataclass_fields(annotation)
+        elif _is_typeddict(annotation):
+            fields = _typeddict_fields(annotation)
+        elif origin is dict and args:
+            fields = {key: (args[1], False) for key in value}
+        return {
+            key: _coerce(item, fields.get(key, (Any, False))[0])
+            for key, item in value.items()
+        }
+
+    if isinstance(value, list):
+        if origin is tuple and args and args[-1] is not Ellipsis:
+            annotations = list(args) + [Any] * (len(value) - len(args))
+        elif origin in (list, tuple) and args:
+            annotations = [args[0]] * len(value)
+        else:
+            annotations = [Any] * len(value)
+        return [
+            _coerce(item, annotation)
+            for item, annotation in zip(value, annotations)
+        ]
+
+    return value
+
+
+def _is_optional(annotation: Any) -> bool:
+    origin = typing.get_origin(annotation)
+    if origin is not Union and origin is not getattr(types, "UnionType", None):
+        return False
+    args = typing.get_args(annotation)
+    return len([arg for arg in args if arg is not type(None)]) == 1
+
+
+def _optional_type(annotation: Any) -> Any:
+    return next(
+        arg for arg in typing.get_args(annotation) if arg is not type(None)
+    )
+
+
+_JSON_SCHEMA_TYPES = {"boolean": bool, "integer": int, "number": float}
+
+
+def _coerce_json_schema(schema: Dict[str, Any], value: Any) -> Any:
+    """
+    Returns a copy of `value` where the interpolated strings are converted to
+    the scalar `type` declared for them by the JSON Schema.
+    """
+    if isinstance(value, _Interpolated):
+        declared = schema.get("type", [])
+        declared = [declared] if isinstance(declared, str) else declared
+        if "string" not in declared:
+            for name in declared:
+                if name in _JSON_SCHEMA_TYPES:
+                    converted = _convert(value, _JSON_SCHEMA_TYPES[name])
+                    if not isinstance(converted, str):
+                        return converted
+        return str(value)
+
+    if isinstance(value, dict):
+        properties = schema.get("properties", {})
+        return {
+            key: _coerce_json_schema(properties.get(key, {}), item)
+            for key, item in value.items()
+        }
+
+    if isinstance(value, list):
+        items = schema.get("items")
+        return [
+            _coerce_json_schema(items if isinstance(items, dict) else {}, item)
+            for item in value
+        ]
+
+    return value
+
+
+def _build(value: Any, annotation: Any) -> Any:
+    """
+    Builds, recursively, the object of type `annotation` from the (validated)
+    `value`.
+    """
+    if isinstance(annotation, type) and dataclasses.is_dataclass(annotation):
+        hints = typing.get_type_hints(annotation)
+        return annotation(
+            **{
+                key: _build(item, hints.get(key, Any))
+                for key, item in value.items()
+            }
+        )
+
+    if _is_typeddict(annotation):
+        hints = typing.get_type_hints(annotation)
+        return {
+            key: _build(item, hints.get(key, Any))
+            for key, item in value.items()
+        }
+
+    origin = typing.get_origin(annotation)
+    args = typing.get_args(annotation)
+
+    if origin is Union or origin is getattr(types, "UnionType", None):
+        # NOTE: The value is built as the first type of the union it matches.
+        for arg in args:
+            arg_errors: List[Tuple[str, str]] = []
+            _check(value, arg, _ROOT, arg_errors)
+            if not arg_errors:
+                return _build(value, arg)
+        return value
+
+    if origin is tuple and args:
+        if args[-1] is Ellipsis:
+            return tuple(_build(item, args[0]) for item in value)
+        return tuple(_build(item, arg) for item, arg in zip(value, args))
+
+    if origin is list and args:
+        return [_build(item, args[0]) for item in value]
+
+    if origin is dict and args:
+        return {key: _build(item, args[1]) for key, item in value.items()}
+
+    return value
+
+
+def _apply_json_schema_defaults(schema: Dict[str, Any], value: Any) -> Any:
+    """
+    Returns a copy of `value` where the absent properties take the `default`
+    declared in the schema.
+    """
+    if isinstance(value, dict):
+        properties = schema.get("properties", {})
+        value = {
+            key: _apply_json_schema_defaults(properties.get(key, {}), item)
+            for key, item in value.items()
+        }
+        for key, property in properties.items():
+            if key not in value and "default" in property:
+                value[key] = copy.deepcopy(property["default"])
+        return value
+
+    if isinstance(value, list) and isinstance(schema.get("items"), dict):
+        return [
+            _apply_json_schema_defaults(schema["items"], item) for item in value
+        ]
+
+    return value
+
+
+def _is_pydantic_model(schema: Any) -> bool:
+    return isinstance(schema, type) and (
+        hasattr(schema, "model_validate") or hasattr(schema, "parse_obj")
+    )
+
+
+def _validate_pydantic(
+    schema: Any, configuration: Any, errors: List[Tuple[str, str]]
+) -> None:
+    try:
+        if hasattr(schema, "model_validate"):
+            schema.model_validate(configuration)
+        else:
+            schema.parse_obj(configuration)
+    except Exception as e:
+        if not callable(getattr(e, "errors", None)):
+            raise
+        for error in e.errors():
+            errors.append((_path(_ROOT, *error["loc"]), error["msg"]))
+
+
+def _validate_json_schema(
+    schema: Dict[str, Any], configuration: Any, errors: List[Tuple[str, str]]
+) -> None:
+    try:
+        import jsonschema
+    except ImportError as e:
+        raise ImportError(
+            "Validating a configuration against a JSON Schema requires the "
+            "`jsonschema` package"
+        ) from e
+
+    validator = jsonschema.validators.validator_for(schema)(schema)
+    for error in validator.iter_errors(configuration):
+        errors.append((_path(_ROOT, *error.absolute_path), error.message))
+
+
+def _check(
+    value: Any, annotation: Any, path: str, errors: List[Tuple[str, str]]
+) -> None:
+    """
+    Checks, recursively, that `value` matches the type `annotation`.
+    """
+    if annotation is Any or annotation is object:
+        return
+
+    if annotation is None or annotation is type(None):
+        if value is not None:
+            errors.append((path, f"expected None, got {_type_name(value)}"))
+        return
+
+    if isinstance(annotation, type) and dataclasses.is_dataclass(annotation):
+        _check_fields(value, _dataclass_fields(annotation), path, errors)
+        return
+
+    if _is_typeddict(annotation):
+        _check_fields(value, _typeddict_fields(annotation), path, errors)
+        return
+
+    origin = typing.get_origin(annotation)
+    args = typing.get_args(annotation)
+
+    if origin is Union or origin is getattr(types, "UnionType", None):
+        # NOTE: `Optional[T]` is checked as `T` to report precise errors.
+        not_none = [arg for arg in args if arg is not type(None)]
+        if value is None and len(not_none) < len(args):
+            return
+        if len(not_none) == 1:
+            _check(value, not_none[0], path, errors)
+            return
+
+        for arg in args:
+            arg_errors: List[Tuple[str, str]] = []
+            _check(value, arg, path, arg_errors)
+            if not arg_errors:
+                return
+        errors.append(
+            (
+                path,
+                f"expected {_annotation_name(annotation)}, "
+                f"got {_type_name(value)}",
+            )
+        )
+        return
+
+    if origin is Literal:
+        if value not in args:
+            errors.append(
+                (path, f"expected one of {list(args)!r}, got {value!r}")
+            )
+        return
+
+    # NOTE: A configuration is JSON, a tuple is hence received as a list.
+    if origin in (list, tuple) or annotation in (list, tuple):
+        if not isinstance(value, list):
+            errors.append((path, f"expected a list, got {_type_name(value)}"))
+            return
+        if origin is tuple and args and args[-1] is not Ellipsis:
+            if len(value) != len(args):
+                errors.append(
+                    (
+                        path,
+                        f"expected {len(args)} elements, got {len(value)}",
+                    )
+                )
+                return
+            for index, (item, arg) in enumerate(zip(value, args)):
+                _check(item, arg, _path(path, index), errors)
+        elif args:
+            for index, item in enumerate(value):
+                _check(item, args[0], _path(path, index), errors)
+        return
+
+    if origin is dict or annotation is dict:
+        if not isinstance(value, dict):
+            errors.append(
+                (path, f"expected a mapping, got {_type_name(value)}")
+            )
+            return
+        if args:
+            for key, item in value.items():
+                _check(item, args[1], _path(path, key), errors)
+        return
+
+    if origin is not None:
+        annotation = origin
+
+    if not isinstance(annotation, type):
+        # NOTE: Annotations we do not understand (e.g. a `TypeVar`) are not
+        #       checked rather than rejected.
+        return
+
+    # NOTE: `bool` is a subclass of `int`, and JSON does not distinguish
+    #       integers from floats.
+    if annotation is float:
+        valid = isinstance(value, (int, float)) and not isinstance(value, bool)
+    elif annotation is int:
+        valid = isinstance(value, int) and not isinstance(value, bool)
+    else:
+        valid = isinstance(value, annotation)
+
+    if not valid:
+        errors.append(
+            (
+                path,
+                f"expected {_annotation_name(annotation)}, "
+                f"got {_type_name(value)}",
+            )
+        )
+
+
+def _check_fields(
+    value: Any,
+    fields: Dict[str, Tuple[Any, bool]],
+    path: str,
+    errors: List[Tuple[str, str]],
+) -> None:
+    """
+    Checks that `value` is a mapping with the provided fields, each described
+    by its annotation and whether it is required.
+    """
+    if not isinstance(value, dict):
+        errors.append((path, f"expected a mapping, got {_type_name(value)}"))
+        return
+
+    for key in value:
+        if key not in fields:
+            message = "unknown field"
+            suggestions = difflib.get_close_matches(str(key), fields, n=1)
+            if suggestions:
+                message += f", did you mean '{suggestions[0]}'?"
+            errors.append((_path(path, key), message))
+
+    for name, (annotation, required) in fields.items():
+        if name in value:
+            _check(value[name], annotation, _path(path, name), errors)
+        elif required:
+            errors.append((_path(path, name), "missing required field"))
+
+
+def _dataclass_fields(cls: type) -> Dict[str, Tuple[Any, bool]]:
+    hints = typing.get_type_hints(cls)
+    return {
+        field.name: (
+            hints.get(field.name, Any),
+            field.default is dataclasses.MISSING
+            and field.default_factory is dataclasses.MISSING,
+        )
+        for field in dataclasses.fields(cls)
+        if field.init
+    }
+
+
+def _is_typeddict(annotation: Any) -> bool:
+    return (
+        isinstance(annotation, type)
+        and issubclass(annotation, dict)
+        and hasattr(annotation, "__total__")
+    )
+
+
+def _typeddict_fields(cls: type) -> Dict[str, Tuple[Any, bool]]:
+    hints = typing.get_type_hints(cls)
+    # NOTE: `__required_keys__` was introduced in Python 3.9.
+    required_keys = getattr(
+        cls, "__required_keys__", hints.keys() if cls.__total__ else ()
+    )
+    return {
+        name: (annotation, name in required_keys)
+        for name, annotation in hints.items()
+    }
+
+
+def _path(path: str, *keys: Any) -> str:
+    for key in keys:
+        path += f"[{key}]" if isinstance(key, int) else f".{key}"
+    return path
+
+
+def _type_name(value: Any) -> str:
+    return type(value).__name__
+
+
+def _annotation_name(annotation: Any) -> str:
+    if isinstance(annotation, type):
+        return annotation.__name__
+    return str(annotation).replace("typing.", "")
@@ -0,0 +1,300 @@
+#
+# Copyright © 2022 ZettaScale Technology
+#
+# This program and the accompanying materials are made available under the
+# terms of the Eclipse Public License 2.0 which is available at
+# http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+# which is available at https://www.apache.org/licenses/LICENSE-2.0.
+#
+# SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+#
+# Contributors:
+#   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+#
+
+"""
+Loading of the code of a node.
+
+The Zenoh-Flow runtime selects the wrapper of a node from the extension of its
+`library`, which must hence be a Python file, e.g. `/path/to/my_node.py`. If
+the file is part of a package (i.e. its folder contains an `__init__.py`), that
+package is loaded as well so that the relative imports of the file work: a
+package is loaded by pointing at its `__init__.py`.
+
+The code can instead be loaded from, as set in the `zenoh_flow_python` section
+of the configuration of the node:
+- `module`: a module, or a `module:Class` spec, e.g.
+  `my_package.nodes:MyOperator`, importable from the `sys.path` of the
+  interpreter;
+- `archive`: a zip archive, e.g. `/path/to/my_node.zip`, with an `__init__.py`
+  at its root;
+- `entry_point`: the name of an entry point of the `zenoh_flow.nodes` group,
+  declared by an installed distribution, e.g. `my-operator`. The entry point
+  can reference a module or any of the attributes described below.
+The `library` is then not loaded, it only selects the wrapper.
+
+Except for a `module` or an entry point, the code is loaded in a module
+namespace unique to the node: several nodes, even loaded from the same library,
+never collide in `sys.modules`.
+
+The class of the node is then looked up in the loaded module:
+1. if `register` is set in the `zenoh_flow_python` section of the
+   configuration of the node, through the attribute it names;
+2. otherwise, among the classes registered with the `@source`, `@operator`
+   and `@sink` decorators (see the `zenoh_flow_python.registry` module);
+3. otherwise, through its `register` function.
+
+An attribute, or what `register` returns, can be the class of the node, a
+function returning it, or a dictionary mapping node ids to classes: a single
+module can then hold several nodes.
+"""
+
+import importlib
+import importlib.metadata
+import importlib.util
+import inspect
+import itertools
+import os
+import re
+import sys
+import types
+import zipfile
+from typing import Any, Dict, Optional
+
+from .registry import registered_nodes
+
+__all__ = ["ENTRY_POINT_GROUP", "load"]
+
+ENTRY_POINT_GROUP = "zenoh_flow.nodes"
+
+_REGISTER = "register"
+_INIT = "__init__.py"
+_MODULE = re.compile(r"^([A-Za-z_][\w.]*)(?::([A-Za-z_][\w.]*))?$")
+
+_counter = itertools.count()
+
+
+def load(
+    library: str,
+    node_id: str,
+    kind: str,
+    module: Optional[str] = None,
+    archive: Optional[str] = None,
+    entry_point: Optional[str] = None,
+    register: Optional[str] = None,
+) -> Any:
+    """
+    Loads the code of the node `node_id`, of the provided `kind` ("Source",
+    "Operator" or "Sink"), and returns the class of the node.
+
+    Raises `ImportError` if the code could not be found or loaded, or if it
+    does not provide a class for the node.
+    """
+    node = _Node(node_id, kind, register)
+
+    sources = {"module": module, "archive": archive, "entry_point": entry_point}
+    set_sources = [name for name, value in sources.items() if value is not None]
+    if len(set_sources) > 1:
+        raise ImportError(
+            f"Only one of `module`, `archive` or `entry_point` can be set, found: "
+            f"{', '.join(f'`{name}`' for name in set_sources)}"
+        )
+
+    if entry_point is not None:
+        return _load_entry_point(entry_point, node)
+
+    if module is not None:
+        spec = _MODULE.match(module)
+        if spec is None:
+            raise ImportError(
+                f"< {module} > is not a valid `module` or `module:Class` spec"
+            )
+        return node.resolve(_load_module(spec.group(1), spec.group(2)), module)
+
+    namespace = _namespace(node_id)
+    if archive is not None:
+        if not zipfile.is_zipfile(archive):
+            raise ImportError(f"< {archive} > is not a zip archive")
+        return node.resolve(_load_zip(namespace, archive), archive)
+
+    if not os.path.isfile(library):
+        raise ImportError(f"< {library} > is not a file")
+    return node.resolve(_load_file(namespace, library), library)
+
+
+class _Node:
+    """The node being loaded, to look its class up."""
+
+    def __init__(self, node_id: str, kind: str, register: Optional[str]):
+        self.node_id = node_id
+        self.kind = kind
+        self.register = register
+
+    def resolve(self, target: Any, origin: str) -> Any:
+        """Returns the class of the node from the `target` found in `origin`."""
+        if inspect.ismodule(target):
+            return self._resolve_module(target, origin)
+        if inspect.isclass(target):
+            return target
+        if isinstance(target, dict):
+            if self.node_id not in target:
+                raise ImportError(
+                    f"< {origin} > provides no class for the node `{self.node_id}`, "
+                    f"it provides: {', '.join(sorted(map(str, target)))}"
+                )
+            return self.resolve(target[self.node_id], origin)
+        if callable(target):
+            return self.resolve(target(), origin)
+        raise ImportError(
+            f"< {origin} > must provide a class, a function returning it or a "
+            f"dictionary of classes, found: {type(target).__name__}"
+        )
+
+    def _resolve_module(self, module: types.ModuleType, origin: str) -> Any:
+        if self.register is not None:
+            if not hasattr(module, self.register):
+                raise ImportError(
+                    f"< {origin} > does not expose the attribute `{self.register}`"
+                )
+            return self.resolve(getattr(module, self.register), origin)
+
+        registered = registered_nodes(module)
+        if registered:
+            return self._select(registered, origin)
+
+        register = getattr(module, _REGISTER, None)
+        if register is None:
+            raise ImportError(
+                f"< {origin} > does not expose a `{_REGISTER}` function nor any "
+                "class decorated with `@source`, `@operator` or `@sink`"
+            )
+        return self.resolve(register, origin)
+
+    def _select(self, registered: Dict[str, Any], origin: str) -> Any:
+        candidates = [node for node in registered.values() if node.kind == self.kind]
+        matches = [node for node in candidates if node.node_id == self.node_id]
+        if not matches:
+            matches = [node for node in candidates if node.node_id is None]
+
+        if len(matches) == 1:
+            return matches[0].node_class
+        if not matches:
+            raise ImportError(
+                f"< {origin} > registers no {self.kind} for the node `{self.node_id}`"
+            )
+        raise ImportError(
+            f"< {origin} > registers several {self.kind} classes for the node "
+            f"`{self.node_id}`: "
+            f"{', '.join(node.node_class.__qualname__ for node in matches)}, "
+            "set their node id in the decorator or `register` in the "
+            "configuration"
+        )
+
+
+def _namespace(node_id: str) -> str:
+    """Returns a module name, unique to this node, that is a valid identifier."""
+    return f"_zenoh_flow_node_{re.sub(r'[^0-9A-Za-z_]', '_', node_id)}_{next(_counter)}"
+
+
+def _load_module(module_name: str, qualified_name: Optional[str]) -> Any:
+    target: Any = importlib.import_module(module_name)
+    if qualified_name is None:
+        return target
+
+    for attribute in qualified_name.split("."):
+        try:
+            target = getattr(target, attribute)
+        except AttributeError:
+            raise ImportError(
+                f"Module `{module_name}` has no attribute `{qualified_name}`"
+            ) from None
+    return target
+
+
+def _load_entry_point(name: str, node: _Node) -> Any:
+    entry_points = importlib.metadata.entry_points()
+    if hasattr(entry_points, "select"):
+        group = entry_points.select(group=ENTRY_POINT_GROUP)
+    else:
+        # Python < 3.10: `entry_points` returns a dictionary indexed by group.
+        group = entry_points.get(ENTRY_POINT_GROUP, [])
+
+    matches = {entry_point.value for entry_point in group if entry_point.name == name}
+    if not matches:
+        raise ImportError(
+            f"No entry point `{name}` in the group `{ENTRY_POINT_GROUP}`, is the "
+            "distribution providing it installed?"
+        )
+    if len(matches) > 1:
+        raise ImportError(
+            f"Entry point `{name}` of the group `{ENTRY_POINT_GROUP}` is ambiguous, "
+            f"it references: {', '.join(sorted(matches))}"
+        )
+
+    target = importlib.metadata.EntryPoint(
+        name=name, value=matches.pop(), group=ENTRY_POINT_GROUP
+    ).load()
+    return node.resolve(target, f"entry point `{name}`")
+
+
+def _exec(name: str, location: str, package_path: Any = None) -> types.ModuleType:
+    spec = importlib.util.spec_from_file_location(
+        name, location, submodule_search_locations=package_path
+    )
+    if spec is None or spec.loader is None:
+        raise ImportError(f"Cannot load < {location} >")
+
+    module = importlib.util.module_from_spec(spec)
+    sys.modules[name] = module
+    try:
+        spec.loader.exec_module(module)
+    except BaseException:
+        del sys.modules[name]
+        raise
+    return module
+
+
+def _load_package(namespace: str, directory: str) -> types.ModuleType:
+    init = os.path.join(directory, _INIT)
+    if not os.path.isfile(init):
+        raise ImportError(f"< {directory} > is not a package: it has no `{_INIT}`")
+    return _exec(namespace, init, [directory])
+
+
+def _load_file(namespace: str, path: str) -> types.ModuleType:
+    directory, file_name = os.path.split(os.path.abspath(path))
+    if not os.path.isfile(os.path.join(directory, _INIT)):
+        return _exec(namespace, path)
+
+    # The file belongs to a package: the package is loaded in the namespace of
+    # the node and the file as one of its submodules, the relative imports then
+    # resolve within that package.
+    _load_package(namespace, directory)
+    module_name = os.path.splitext(file_name)[0]
+    if module_name == "__init__":
+        return sys.modules[namespace]
+    return importlib.import_module(f"{namespace}.{module_name}")
+
+
+def _load_zip(namespace: str, archive: str) -> types.ModuleType:
+    with zipfile.ZipFile(archive) as zip_file:
+        try:
+            source = zip_file.read(_INIT)
+        except KeyError:
+            raise ImportError(
+                f"< {archive} > is not a package: it has no `{_INIT}` at its root"
+            ) from None
+
+    # The submodules of the package are found through `__path__`, the archive
+    # itself being handled by `zipimport`.
+    module = types.ModuleType(namespace)
+    module.__file__ = os.path.join(archive, _INIT)
+    module.__path__ = [archive]
+    module.__package__ = namespace
+    sys.modules[namespace] = module
+    try:
+        exec(compile(source, module.__file__, "exec"), module.__dict__)
+    except BaseException:
+        del sys.modules[namespace]
+        raise
+    return module
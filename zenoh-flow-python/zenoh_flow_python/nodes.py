@@ -13,36 +13,37 @@
 #
 
 from zenoh_flow_python import Context, Inputs, Outputs
-from typing import Dict, Any
+from typing import Any, ClassVar, Dict
 from abc import ABC, abstractmethod
 
 
-class Operator(ABC):
+class Node(ABC):
     """
-    An `Operator` is a node performing transformation over the data it receives,
-    outputting the end result to downstream node(s).
-
-    An Operator hence possesses at least one Input and one Output.
+    The behaviour shared by the `Operator`, `Source` and `Sink` nodes.
     """
 
-    @abstractmethod
-    def __init__(
-            self,
-            context: Context,
-            configuration: Dict[str, Any],
-            inputs: Inputs,
-            outputs: Outputs,
-    ):
-        """
-        The constructor is called once by the Zenoh-Flow runtime when the data
-        flow is loaded.
-        """
+    configuration_schema: ClassVar[Any] = None
+    """
+    The schema of the configuration of the node: a dataclass, a `TypedDict`,
+    a pydantic model or a JSON Schema (as a dictionary).
+
+    If set, the environment variables referenced in the configuration are
+    interpolated and converted to their declared type, the configuration is
+    validated against the schema and the constructor receives an instance of it
+    (with its defaults) instead of a dictionary. See the
+    `zenoh_flow_python.configuration` module.
+    """
 
     @abstractmethod
     async def iteration(self) -> None:
         """
         The `iteration` is called by the Zenoh-Flow runtime in a loop once the
         data flow is started.
+
+        It runs on an asyncio event loop dedicated to the node, which keeps
+        running between iterations: the tasks created with
+        `asyncio.create_task` make progress in the background until the node
+        is destroyed, at which point they are cancelled.
         """
 
     @abstractmethod
@@ -52,12 +53,30 @@ class Operator(ABC):
         destroying the node (e.g., upon stopping the data flow graph).
         """
 
+    def on_configuration_update(self, configuration: Any) -> None:
+        """
+        The `on_configuration_update` method is called by the Zenoh-Flow
+        Python wrapper when a new configuration is pushed.
 
-class Source(ABC):
+        Updates are read from the file set in the `configuration_file` entry
+        of the `zenoh_flow_python` section of the configuration, polled every
+        `configuration_poll_interval_ms` (1000 by default). A YAML file
+        requires the PyYAML package (`zenoh-flow-python[yaml]`). The updates
+        are converted and validated exactly as the initial configuration: an
+        invalid update is logged and ignored.
+
+        This method runs on the event loop of the node, even while an
+        `iteration` is waiting (e.g. on an input). It can also be a coroutine.
+        By default, updates are ignored.
+        """
+
+
+class Operator(Node):
     """
-    A `Source` feeds data into a data flow.
+    An `Operator` is a node performing transformation over the data it receives,
+    outputting the end result to downstream node(s).
 
-    As such, a Source only possesses Output(s).
+    An Operator hence possesses at least one Input and one Output.
     """
 
     @abstractmethod
@@ -65,6 +84,7 @@ class Source(ABC):
             self,
             context: Context,
             configuration: Dict[str, Any],
+            inputs: Inputs,
             outputs: Outputs,
     ):
         """
@@ -72,22 +92,28 @@ class Source(ABC):
         flow is loaded.
         """
 
-    @abstractmethod
-    async def iteration(self) -> None:
-        """
-        The `iteration` is called by the Zenoh-Flow runtime in a loop once the
-        data flow is started.
-        """
+
+class Source(Node):
+    """
+    A `Source` feeds data into a data flow.
+
+    As such, a Source only possesses Output(s).
+    """
 
     @abstractmethod
-    def finalize(self) -> None:
+    def __init__(
+            self,
+            context: Context,
+            configuration: Dict[str, Any],
+            outputs: Outputs,
+    ):
         """
-        The `finalize` method is called by the Zenoh-Flow runtime before
-        destroying the node (e.g., upon stopping the data flow graph).
+        The constructor is called once by the Zenoh-Flow runtime when the data
+        flow is loaded.
         """
 
 
-class Sink(ABC):
+class Sink(Node):
     """
     A `Sink` exposes the outcome of the data flow processing.
 
@@ -105,17 +131,3 @@ class Sink(ABC):
         The constructor is called once by the Zenoh-Flow runtime when the data
         flow is loaded.
         """
-
-    @abstractmethod
-    async def iteration(self) -> None:
-        """
-        The `iteration` is called by the Zenoh-Flow runtime in a loop once the
-        data flow is started.
-        """
-
-    @abstractmethod
-    def finalize(self) -> None:
-        """
-        The `finalize` method is called by the Zenoh-Flow runtime before
-        destroying the node (e.g., upon stopping the data flow graph).
-        """
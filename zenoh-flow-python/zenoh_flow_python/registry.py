@@ -0,0 +1,113 @@
+#
+# Copyright © 2022 ZettaScale Technology
+#
+# This program and the accompanying materials are made available under the
+# terms of the Eclipse Public License 2.0 which is available at
+# http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+# which is available at https://www.apache.org/licenses/LICENSE-2.0.
+#
+# SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+#
+# Contributors:
+#   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
+#
+
+"""
+Decorators registering the classes of the nodes of a module.
+
+```python
+import zenoh_flow_python
+
+@zenoh_flow_python.operator
+class MyOperator(zenoh_flow_python.nodes.Operator):
+    ...
+
+@zenoh_flow_python.sink("logger")
+class MySink(zenoh_flow_python.nodes.Sink):
+    ...
+```
+
+A class decorated without a node id is used by any node of its kind loading
+the module; with a node id, it is only used by the node having that id. Such a
+module does not need a `register` function.
+
+The classes of a package are the ones registered in the package itself and in
+its submodules, provided they are imported when the package is loaded (e.g. by
+its `__init__.py`).
+"""
+
+import sys
+from typing import Any, Callable, Dict, NamedTuple, Optional, TypeVar, Union, overload
+
+__all__ = ["RegisteredNode", "operator", "registered_nodes", "sink", "source"]
+
+_REGISTRY = "__zenoh_flow_nodes__"
+
+T = TypeVar("T", bound=type)
+
+
+class RegisteredNode(NamedTuple):
+    """A class registered through a decorator."""
+
+    kind: str
+    node_id: Optional[str]
+    node_class: type
+
+
+def registered_nodes(module: Any) -> Dict[str, RegisteredNode]:
+    """
+    Returns the classes registered in `module` and, if it is a package, in its
+    imported submodules, indexed by their qualified name. The qualified name of
+    a class of a submodule is prefixed by the name of the submodule, relative
+    to the package.
+    """
+    registered = dict(getattr(module, _REGISTRY, {}))
+    if not hasattr(module, "__path__"):
+        return registered
+
+    prefix = f"{module.__name__}."
+    for name, submodule in sorted(sys.modules.items()):
+        if name.startswith(prefix):
+            for qualname, node in getattr(submodule, _REGISTRY, {}).items():
+                registered[f"{name[len(prefix):]}.{qualname}"] = node
+    return registered
+
+
+def _register(kind: str, node_id: Optional[str], node_class: T) -> T:
+    if not isinstance(node_class, type):
+        raise TypeError(f"@{kind.lower()} can only decorate a class")
+
+    module = sys.modules[node_class.__module__]
+    registry = module.__dict__.setdefault(_REGISTRY, {})
+    registry[node_class.__qualname__] = RegisteredNode(kind, node_id, node_class)
+    return node_class
+
+
+def _decorator(kind: str) -> Callable[..., Any]:
+    @overload
+    def decorator(node_id: T) -> T: ...
+
+    @overload
+    def decorator(node_id: Optional[str] = None) -> Callable[[T], T]: ...
+
+    def decorator(node_id: Union[T, str, None] = None) -> Union[T, Callable[[T], T]]:
+        # `@operator`: the decorator receives the class directly.
+        if node_id is not None and not isinstance(node_id, str):
+            return _register(kind, None, node_id)
+
+        def register(node_class: T) -> T:
+            return _register(kind, node_id, node_class)
+
+        return register
+
+    decorator.__name__ = kind.lower()
+    decorator.__doc__ = (
+        f"Registers the decorated class as a {kind}, optionally only for the "
+        "node with the provided id."
+    )
+    return decorator
+
+
+source = _decorator("Source")
+operator = _decorator("Operator")
+sink = _decorator("Sink")
@@ -12,7 +12,69 @@
 #   ZettaScale Zenoh Team, <zenoh@zettascale.tech>
 #
 
-from typing import final
+import datetime
+from typing import TYPE_CHECKING, Any, Dict, List, Protocol, Tuple, Union, final
+
+if TYPE_CHECKING:
+    # Optional dependencies, only required by the codecs that use them.
+    import numpy
+    import pandas
+    import pyarrow
+
+# Any object implementing the buffer protocol: `bytes`, `bytearray`,
+# `memoryview`, `array.array`, numpy arrays, ...
+Buffer = Union[bytes, bytearray, memoryview, Any]
+
+
+class ZenohFlowError(ValueError):
+    """
+    Base class of all the errors raised by Zenoh-Flow.
+
+    It derives from `ValueError`, the type of the errors raised by previous
+    versions: existing `except ValueError` handlers keep working.
+    """
+    chain: List[str]
+    """
+    The messages of the error and of all its causes, outermost first.
+    """
+
+
+class DisconnectedError(ZenohFlowError):
+    """
+    Raised when receiving on, or sending to, a channel that is disconnected.
+    """
+
+
+class ChannelFullError(ZenohFlowError):
+    """
+    Raised by `try_send` when the message could not be sent without blocking.
+    """
+
+
+class PortNotFoundError(ZenohFlowError):
+    """
+    Raised when taking a port that does not exist or that was already taken.
+    """
+
+
+class SerializationError(ZenohFlowError):
+    """
+    Raised when a payload could not be serialised or deserialised.
+    """
+
+
+class Codec(Protocol):
+    """
+    Any object exposing `encode` and / or `decode` callables.
+
+    A typed `Output` only requires `encode` while a typed `Input` only
+    requires `decode`. `encode` can return any bytes-like object.
+
+    `decode` receives a read-only `memoryview` over the bytes of the payload,
+    it can call `bytes(payload)` if it needs a copy that outlives the call.
+    """
+    def encode(self, data: Any) -> Buffer: ...
+    def decode(self, payload: memoryview) -> Any: ...
 
 
 @final
@@ -27,6 +89,53 @@ class RuntimeId:
     def __str__(self) -> str: ...
 
 
+@final
+class Configuration:
+    """
+    The configuration of a node, exactly as the Zenoh-Flow runtime passes it
+    to the constructor of the node.
+
+    The conversion follows what the runtime does when parsing a configuration
+    from a descriptor: integers that fit neither in a signed nor in an
+    unsigned 64 bits integer are converted to floats, and non-finite floats
+    (NaN and infinities) are converted to None.
+    """
+    def __init__(self, configuration: Any = None) -> None:
+        """
+        Creates a Configuration from None, a boolean, a number, a string, a
+        list, a tuple, a dictionary with string keys or a Configuration.
+
+        # Exception
+
+        A `ZenohFlowError` is raised if the object, or any object it contains,
+        cannot be converted.
+        """
+
+    @staticmethod
+    def from_json(json: str) -> Configuration:
+        """
+        Parses a Configuration from its JSON representation.
+
+        # Exception
+
+        A `ZenohFlowError` is raised if `json` is not valid JSON.
+        """
+
+    def to_py(self) -> Any:
+        """
+        Returns the Python objects (dictionaries, lists, strings, ...) of this
+        Configuration, as they are passed to the constructor of the nodes.
+        """
+
+    def to_json(self) -> str:
+        """
+        Returns the JSON representation of this Configuration.
+        """
+
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+
+
 @final
 class Context:
     """The execution context of a data flow instance."""
@@ -71,13 +180,41 @@ class Timestamp:
     """
     A timestamp made of a NTP64 and a Hybrid Logical Clock (HLC) unique
     identifier.
+
+    Timestamps are totally ordered (first by time, then by identifier) and
+    hashable. Their string representation matches the one of Zenoh-Flow:
+    `<ntp64>/<id>`.
     """
 
-    @property
+    def __init__(self, ntp64: int, id: str) -> None:
+        """
+        Creates a Timestamp from its raw NTP64 time and the hexadecimal
+        representation of the identifier of an HLC.
+        """
+
+    @staticmethod
+    def from_datetime(datetime: datetime.datetime, id: str) -> Timestamp:
+        """
+        Creates a Timestamp from a `datetime.datetime` and the hexadecimal
+        representation of the identifier of an HLC.
+
+        A naive `datetime` is interpreted as local time.
+
+        # Exception
+
+        An exception is raised if the `datetime` is before UNIX_EPOCH or if the
+        identifier is not valid.
+        """
+
     def time(self) -> int:
         """Returns the number of milliseconds elapsed since UNIX EPOCH."""
 
-    @property
+    def time_ns(self) -> int:
+        """Returns the number of nanoseconds elapsed since UNIX EPOCH."""
+
+    def ntp64(self) -> int:
+        """Returns the raw NTP64 time of the timestamp."""
+
     def id(self) -> str:
         """
         Returns the unique identifier of the HLC that generated the timestamp.
@@ -85,6 +222,21 @@ class Timestamp:
         The value is a hexadecimal representation.
         """
 
+    def to_datetime(self) -> datetime.datetime:
+        """
+        Returns the time of the timestamp as a timezone-aware (UTC)
+        `datetime.datetime`.
+
+        As `datetime` has a resolution of a microsecond, the nanoseconds are
+        truncated.
+        """
+
+    def __lt__(self, other: Timestamp) -> bool: ...
+    def __le__(self, other: Timestamp) -> bool: ...
+    def __gt__(self, other: Timestamp) -> bool: ...
+    def __ge__(self, other: Timestamp) -> bool: ...
+    def __hash__(self) -> int: ...
+
 
 @final
 class LinkMessage:
@@ -96,9 +248,18 @@ class LinkMessage:
     """
 
     @property
-    def payload(self) -> bytes:
+    def payload(self) -> memoryview:
         """
-        Returns the payload, as bytes, associated with this message.
+        Returns a read-only `memoryview` over the payload associated with this
+        message.
+
+        The bytes are not copied: the view borrows the memory in which the
+        payload was received. Call `bytes(...)` on the view to obtain a copy.
+
+        # Exception
+
+        A `SerializationError` will be raised if the payload was sent, typed,
+        by a node running in the same process and its serialisation failed.
         """
 
     @property
@@ -112,16 +273,36 @@ class LinkMessage:
 class InputRaw:
     """
     A raw Zenoh-Flow Input, receiving serialised payload from upstream nodes.
+
+    A raw Input is an asynchronous iterator over the messages it receives.
     """
-    async def recv_async(self) -> LinkMessage:
+    async def recv_async(self, timeout_ms: int | None = None) -> LinkMessage | None:
         """
         Retrieves, asynchronously, a message from the raw Input.
 
+        If a timeout is provided and no message was received before it
+        expired, None is returned.
+
         # Exception
 
-        An exception will be raised if the underlying channel is disconnected. A
-        channel is disconnected only if the process holding the sending end is
-        stopped (voluntarily or not).
+        A `DisconnectedError` will be raised if the underlying channel is
+        disconnected. A channel is disconnected only if the process holding the
+        sending end is stopped (voluntarily or not).
+        """
+
+    async def recv_until(self, deadline_ms: int) -> LinkMessage | None:
+        """
+        Retrieves, asynchronously, a message from the raw Input or returns None
+        if none was received before the deadline.
+
+        The deadline is interpreted as the number of milliseconds that elapsed
+        since UNIX_EPOCH. A deadline in the past behaves as `try_recv`.
+
+        # Exception
+
+        A `DisconnectedError` will be raised if the underlying channel is
+        disconnected. A channel is disconnected only if the process holding the
+        sending end is stopped (voluntarily or not).
         """
 
     def try_recv(self) -> LinkMessage | None:
@@ -132,9 +313,9 @@ class InputRaw:
 
         # Exception
 
-        An exception will be raised if the underlying channel is disconnected. A
-        channel is disconnected only if the process holding the sending end is
-        stopped (voluntarily or not).
+        A `DisconnectedError` will be raised if the underlying channel is
+        disconnected. A channel is disconnected only if the process holding the
+        sending end is stopped (voluntarily or not).
         """
 
     def port_id(self) -> str:
@@ -142,12 +323,180 @@ class InputRaw:
         Returns the port id associated with this raw Input.
         """
 
+    async def recv_many(
+        self, max: int, timeout_ms: int | None = None
+    ) -> List[LinkMessage]:
+        """
+        Retrieves, asynchronously, at most `max` messages from the raw Input.
+
+        This method waits for a first message (or, if a timeout is provided,
+        until it expires in which case the list is empty) and then drains,
+        without waiting, the messages that are already queued.
+
+        # Exception
+
+        A `DisconnectedError` will be raised if the underlying channel is
+        disconnected before a first message is received. A channel is
+        disconnected only if the process holding the sending end is stopped
+        (voluntarily or not).
+        """
+
+    def recv_blocking(self, timeout_ms: int | None = None) -> LinkMessage | None:
+        """
+        Blocks until a message is received on the raw Input.
+
+        If a timeout is provided and no message was received before it
+        expired, None is returned.
+
+        The GIL is released while waiting: this method is meant to be called
+        from a worker thread, not from the asyncio event loop.
+
+        # Exception
+
+        A `DisconnectedError` will be raised if the underlying channel is
+        disconnected. A channel is disconnected only if the process holding the
+        sending end is stopped (voluntarily or not).
+        """
+
+    def __aiter__(self) -> InputRaw: ...
+
+    async def __anext__(self) -> LinkMessage:
+        """
+        Retrieves, asynchronously, the next message from the raw Input, which
+        allows iterating over the messages it receives:
+
+            async for message in input_raw:
+                ...
+
+        The iteration stops once the underlying channel is disconnected.
+        """
+
+
+@final
+class Selector:
+    """
+    Selects, among several raw Inputs, the first one that receives a message.
+
+    Each Selector keeps its own polling order: create one per loop, and reuse
+    it at each iteration.
+
+        selector = Selector([left, right])
+        while True:
+            port_id, message = await selector.recv_async()
+
+    No message is lost on the Inputs that were not selected.
+    """
+    def __init__(self, inputs: List[InputRaw], biased: bool = False) -> None:
+        """
+        If `biased` is False, the Inputs are polled in a rotating order such
+        that a busy Input cannot starve the others. If `biased` is True, they
+        are polled in the order in which they are provided: this order then
+        acts as a priority.
+
+        # Exception
+
+        A `ZenohFlowError` is raised if no Input is provided.
+        """
+
+    async def recv_async(self) -> Tuple[str, LinkMessage]:
+        """
+        Retrieves, asynchronously, the first message received on any of the
+        Inputs, along with the port id of the Input that received it.
+
+        # Exception
+
+        A `DisconnectedError`, naming the port, will be raised if the channel
+        of the selected Input is disconnected. A channel is disconnected only
+        if the process holding the sending end is stopped (voluntarily or
+        not).
+        """
+
+
+@final
+class Input:
+    """
+    A typed Zenoh-Flow Input, receiving Python objects from upstream nodes.
+
+    The payloads are decoded with the `decode` method of the codec that was
+    provided when calling `Inputs.take`.
+    """
+    async def recv_async(self) -> Tuple[Any, Timestamp]:
+        """
+        Retrieves, asynchronously, a decoded object and its Timestamp from the
+        Input.
+
+        # Exceptions
+
+        An exception will be raised if:
+        1. The underlying channel is disconnected: `DisconnectedError`. A
+           channel is disconnected only if the process holding the sending end
+           is stopped (voluntarily or not).
+        2. The call to `decode` failed: `SerializationError`.
+        """
+
+    def try_recv(self) -> Tuple[Any, Timestamp] | None:
+        """
+        Attempts to retrieve a decoded object and its Timestamp from the Input.
+
+        If the channel is empty, this method will return None.
+
+        # Exceptions
+
+        An exception will be raised if:
+        1. The underlying channel is disconnected: `DisconnectedError`. A
+           channel is disconnected only if the process holding the sending end
+           is stopped (voluntarily or not).
+        2. The call to `decode` failed: `SerializationError`.
+        """
+
+    def port_id(self) -> str:
+        """
+        Returns the port id associated with this Input.
+        """
+
 
 @final
 class Inputs:
     """
     The channels *receiving* data from upstream nodes.
     """
+    def port_ids(self) -> List[str]:
+        """
+        Returns the (sorted) ids of the Inputs that have not yet been taken.
+        """
+
+    def __contains__(self, port_id: str) -> bool:
+        """
+        Returns True if an Input associated with the provided port id has
+        not yet been taken.
+        """
+
+    def __len__(self) -> int:
+        """
+        Returns the number of Inputs that have not yet been taken.
+        """
+
+    def take_all_raw(self) -> Dict[str, InputRaw]:
+        """
+        Takes all the remaining Inputs, as raw Inputs, indexed by their port
+        id.
+        """
+
+    def take(self, port_id: str, codec: Codec) -> Input:
+        """
+        Returns the typed Input associated with the provided port id, decoding
+        the payloads it receives with the `decode` method of `codec`.
+
+        Note that the port id must be an exact match to what is written in the
+        data flow descriptor.
+
+        # Exception
+
+        If no Input is associated with this port id, a `PortNotFoundError` is
+        raised. If `codec` has no `decode` method, a `ZenohFlowError` is
+        raised.
+        """
+
     def take_raw(self, port_id: str) -> InputRaw:
         """
         Returns the raw Input associated with the provided port id.
@@ -157,7 +506,8 @@ class Inputs:
 
         # Exception
 
-        If no Input is associated with this port id, an exception is raised.
+        If no Input is associated with this port id, a `PortNotFoundError` is
+        raised.
         """
 
 
@@ -166,11 +516,19 @@ class OutputRaw:
     """
     A raw Zenoh-Flow Output, sending serialised payload to downstream nodes.
     """
-    async def send_async(self, payload: bytes, timestamp_ms: int | None) -> None:
+    async def send_async(
+        self, payload: Buffer, timestamp: Timestamp | int | None
+    ) -> None:
         """
         Sends, asynchronously, a payload on the raw Output.
 
-        If a timestamp is provided, it will be interpreted as the number of
+        The payload can be any C-contiguous object implementing the buffer
+        protocol. Its bytes are copied, unless zero-copy was enabled when
+        taking the Output, in which case the object must not be modified after
+        having been sent.
+
+        If a `Timestamp` is provided, it is associated as is with the payload.
+        If an integer is provided, it will be interpreted as the number of
         milliseconds that elapsed since UNIX_EPOCH.
 
         If no timestamp is provided, a new timestamp will be generated by the
@@ -179,16 +537,64 @@ class OutputRaw:
 
         # Exception
 
-        An exception will be raised if any of the underlying channels is
-        disconnected. A channel is disconnected only if the process holding the
-        receiving end is stopped (voluntarily or not).
+        A `DisconnectedError` will be raised if any of the underlying channels
+        is disconnected. A channel is disconnected only if the process holding
+        the receiving end is stopped (voluntarily or not).
         """
 
-    def try_send(self, payload: bytes, timestamp_ms: Timestamp | None) -> None:
+    async def send_many(
+        self,
+        payloads: List[Buffer],
+        timestamps: List[Timestamp | int | None] | None = None,
+    ) -> None:
+        """
+        Sends, asynchronously and in order, all the payloads on the raw Output.
+
+        If timestamps are provided, there must be as many as there are
+        payloads: the i-th timestamp is associated with the i-th payload. Each
+        payload and timestamp is interpreted as in `send_async`.
+
+        # Exceptions
+
+        An exception will be raised if:
+        1. The number of timestamps does not match the number of payloads:
+           `ZenohFlowError`.
+        2. Any of the underlying channels is disconnected: `DisconnectedError`.
+           A channel is disconnected only if the process holding the receiving
+           end is stopped (voluntarily or not).
+        """
+
+    def send_blocking(
+        self, payload: Buffer, timestamp: Timestamp | int | None
+    ) -> None:
+        """
+        Blocks until the payload is sent on the raw Output.
+
+        The GIL is released while waiting: this method is meant to be called
+        from a worker thread, not from the asyncio event loop.
+
+        The payload and timestamp are interpreted as in `send_async`.
+
+        # Exception
+
+        A `DisconnectedError` will be raised if any of the underlying channels
+        is disconnected. A channel is disconnected only if the process holding
+        the receiving end is stopped (voluntarily or not).
+        """
+
+    def try_send(
+        self, payload: Buffer, timestamp: Timestamp | int | None
+    ) -> None:
         """
         Attempts to send a payload on the raw Output.
 
-        If a timestamp is provided, it will be interpreted as the number of
+        The payload can be any C-contiguous object implementing the buffer
+        protocol. Its bytes are copied, unless zero-copy was enabled when
+        taking the Output, in which case the object must not be modified after
+        having been sent.
+
+        If a `Timestamp` is provided, it is associated as is with the payload.
+        If an integer is provided, it will be interpreted as the number of
         milliseconds that elapsed since UNIX_EPOCH.
 
         If no timestamp is provided, a new timestamp will be generated by the
@@ -198,11 +604,8 @@ class OutputRaw:
         # Exceptions
 
         An exception will be raised if:
-        1. Any of the underlying channels is full, hence preventing sending the
-           payload.
-        2. Any of the underlying channels is disconnected. A channel is
-           disconnected only if the process holding the receiving end is stopped
-           (voluntarily or not).
+        1. One of the underlying channels is full: `ChannelFullError`.
+        2. One of the underlying channels is disconnected: `DisconnectedError`.
         """
 
     def port_id(self) -> str:
@@ -211,19 +614,199 @@ class OutputRaw:
         """
 
 
+@final
+class Output:
+    """
+    A typed Zenoh-Flow Output, sending Python objects to downstream nodes.
+
+    The objects are encoded with the `encode` method of the codec that was
+    provided when calling `Outputs.take`. The encoding is only performed if
+    the object has to leave the process: downstream Python nodes running in
+    the same process receive the object as is.
+    """
+    async def send_async(self, data: Any, timestamp: int | None) -> None:
+        """
+        Sends, asynchronously, an object on the Output.
+
+        If a timestamp is provided, it will be interpreted as the number of
+        milliseconds that elapsed since UNIX_EPOCH.
+
+        If no timestamp is provided, a new timestamp will be generated by the
+        Hybrid Logical Clock (HLC) used by the Zenoh-Flow runtime on which the
+        node is executed.
+
+        # Exception
+
+        A `DisconnectedError` will be raised if any of the underlying channels
+        is disconnected. A channel is disconnected only if the process holding
+        the receiving end is stopped (voluntarily or not).
+        """
+
+    def try_send(self, data: Any, timestamp: int | None) -> None:
+        """
+        Attempts to send an object on the Output.
+
+        If a timestamp is provided, it will be interpreted as the number of
+        milliseconds that elapsed since UNIX_EPOCH.
+
+        If no timestamp is provided, a new timestamp will be generated by the
+        Hybrid Logical Clock (HLC) used by the Zenoh-Flow runtime on which the
+        node is executed.
+
+        # Exceptions
+
+        An exception will be raised if:
+        1. One of the underlying channels is full: `ChannelFullError`.
+        2. One of the underlying channels is disconnected: `DisconnectedError`.
+        3. The object had to be encoded and the call to `encode` failed:
+           `SerializationError`.
+        """
+
+    def port_id(self) -> str:
+        """
+        Returns the port id associated with this Output.
+        """
+
+
 @final
 class Outputs:
     """
     The channels *sending* data to downstream nodes.
     """
-    def take_raw(self, port_id: str) -> OutputRaw:
+    def port_ids(self) -> List[str]:
+        """
+        Returns the (sorted) ids of the Outputs that have not yet been taken.
+        """
+
+    def __contains__(self, port_id: str) -> bool:
+        """
+        Returns True if an Output associated with the provided port id has
+        not yet been taken.
+        """
+
+    def __len__(self) -> int:
+        """
+        Returns the number of Outputs that have not yet been taken.
+        """
+
+    def take_all_raw(self, zero_copy: bool = False) -> Dict[str, OutputRaw]:
+        """
+        Takes all the remaining Outputs, as raw Outputs, indexed by their port
+        id.
+
+        See `take_raw` for the meaning of `zero_copy`.
+        """
+
+    def take(self, port_id: str, codec: Codec) -> Output:
+        """
+        Returns the typed Output associated with the provided port id, encoding
+        the objects it sends with the `encode` method of `codec`.
+
+        Note that the port id must be an exact match to what is written in the
+        data flow descriptor.
+
+        # Exception
+
+        If no Output is associated with this port id, a `PortNotFoundError` is
+        raised. If `codec` has no `encode` method, a `ZenohFlowError` is
+        raised.
+        """
+
+    def take_raw(self, port_id: str, zero_copy: bool = False) -> OutputRaw:
         """
         Returns the raw Output associated with the provided port id.
 
+        By default, the payloads sent on the Output are copied. If `zero_copy`
+        is True, they are shared with the receivers running in the same
+        process. Only enable it if all the nodes downstream of the Output are
+        Python nodes: a Rust node running in the same process cannot read such
+        payloads.
+
         Note that the port id must be an exact match to what is written in the
         data flow descriptor.
 
         # Exception
 
-        If no Output is associated with this port id, an exception is raised.
+        If no Output is associated with this port id, a `PortNotFoundError` is
+        raised.
+        """
+
+
+@final
+class NDArrayCodec:
+    """
+    A codec exchanging NumPy `ndarray`s, to use with `Inputs.take` and
+    `Outputs.take`.
+
+    An array is sent with its dtype, shape and strides, followed by its raw
+    bytes. The wire layout is documented in the `ndarray` module of the
+    `zenoh-flow-python` Rust crate, which also exposes an `NDArrayHeader` to
+    encode and decode it from Rust nodes.
+
+    This codec requires `numpy` to be installed.
+    """
+    def __init__(self) -> None: ...
+
+    def encode(self, array: numpy.ndarray) -> bytes:
+        """
+        Encodes the array. Arrays that are neither C- nor Fortran-contiguous
+        are first copied into a C-contiguous array.
+
+        # Exception
+
+        A `SerializationError` is raised if the dtype of the array is
+        structured, a sub-array or contains Python objects.
+        """
+
+    def decode(self, payload: Buffer) -> numpy.ndarray:
+        """
+        Rebuilds a read-only array directly over the memory of the payload,
+        without copying it.
+
+        Combined with `LinkMessage.payload`, this allows receiving an array on
+        an `InputRaw` without any copy:
+
+            array = codec.decode((await input_raw.recv_async()).payload())
+        """
+
+
+@final
+class RecordBatchCodec:
+    """
+    A codec exchanging Apache Arrow record batches, to use with `Inputs.take`
+    and `Outputs.take`.
+
+    A record batch is sent in the Arrow IPC streaming format. Rust nodes can
+    read and write such payloads with the `record_batch_from_ipc` and
+    `record_batch_to_ipc` functions of the `zenoh-flow-python` Rust crate
+    (`arrow` feature).
+
+    This codec requires `pyarrow` to be installed, and `pandas` to encode or
+    decode DataFrames.
+    """
+    def __init__(self, as_pandas: bool = False) -> None:
+        """
+        If `as_pandas` is True, payloads are decoded as `pandas.DataFrame`
+        instead of `pyarrow.RecordBatch`.
+        """
+
+    def encode(
+        self, data: Union[pyarrow.RecordBatch, pandas.DataFrame]
+    ) -> pyarrow.Buffer:
+        """
+        Encodes the record batch, or DataFrame, in the Arrow IPC streaming
+        format.
+
+        The returned buffer can be sent, without copy if zero-copy is enabled,
+        on an `OutputRaw`.
+        """
+
+    def decode(
+        self, payload: Buffer
+    ) -> Union[pyarrow.RecordBatch, pandas.DataFrame]:
+        """
+        Decodes the record batch contained in the payload.
+
+        Combined with `LinkMessage.payload`, the columns of the record batch
+        are not copied: they are built over the memory of the payload.
         """